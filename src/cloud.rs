@@ -16,28 +16,50 @@
 
 #[allow(unused_imports)]
 use futures::io::AsyncRead;
+use std::collections::HashMap;
 #[allow(unused_imports)]
 use std::io;
 
+#[cfg(feature = "compute")]
+use chrono::{DateTime, Utc};
+use osauth::CloudConfig;
+use reqwest::Url;
+
 use super::auth::AuthType;
 #[allow(unused_imports)]
 use super::common::{ContainerRef, FlavorRef, NetworkRef};
 #[cfg(feature = "compute")]
 use super::compute::{
-    Flavor, FlavorQuery, FlavorSummary, KeyPair, KeyPairQuery, NewKeyPair, NewServer, Server,
-    ServerQuery, ServerSummary,
+    self, Flavor, FlavorQuery, FlavorSummary, HypervisorStatistics, KeyPair, KeyPairQuery,
+    Migration, MigrationQuery, NewKeyPair, NewServer, NewServerGroup, QuotaClass, ResourceTotals,
+    Server, ServerGroup, ServerQuery, ServerSummary, TenantUsage, VersionDetails,
 };
 #[cfg(feature = "image")]
 use super::image::{Image, ImageQuery};
 #[cfg(feature = "network")]
 use super::network::{
     FloatingIp, FloatingIpQuery, Network, NetworkQuery, NewFloatingIp, NewNetwork, NewPort,
-    NewRouter, NewSubnet, Port, PortQuery, Router, RouterQuery, Subnet, SubnetQuery,
+    NewRbacPolicy, NewRouter, NewSubnet, NewSubnetPool, Port, PortQuery, RbacAction,
+    RbacObjectType, RbacPolicy, RbacPolicyQuery, Router, RouterQuery, Subnet, SubnetPool,
+    SubnetPoolQuery, SubnetQuery,
 };
 #[cfg(feature = "object-storage")]
 use super::object_storage::{Container, ContainerQuery, NewObject, Object, ObjectQuery};
 use super::session::Session;
-use super::{EndpointFilters, InterfaceType, Result};
+use super::utils;
+use super::{EndpointFilters, Error, ErrorKind, InterfaceType, Result};
+
+/// Catalog types of the services supported with the enabled features.
+const SERVICE_TYPES: &[&str] = &[
+    #[cfg(feature = "compute")]
+    "compute",
+    #[cfg(feature = "image")]
+    "image",
+    #[cfg(feature = "network")]
+    "network",
+    #[cfg(feature = "object-storage")]
+    "object-store",
+];
 
 /// OpenStack cloud API.
 ///
@@ -110,6 +132,22 @@ impl Cloud {
         })
     }
 
+    /// Create a new cloud object from an already loaded configuration.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # async fn cloud_from_cloud_config() -> openstack::Result<()> {
+    /// let config = openstack::auth::CloudConfig::from_config("cloud-1")?;
+    /// let os = openstack::Cloud::from_cloud_config(config).await?;
+    /// # Ok(()) }
+    /// ```
+    pub async fn from_cloud_config(config: CloudConfig) -> Result<Cloud> {
+        Ok(Cloud {
+            session: config.create_session().await?,
+        })
+    }
+
     /// Endpoint filters for this cloud.
     #[inline]
     pub fn endpoint_filters(&self) -> &EndpointFilters {
@@ -141,6 +179,141 @@ impl Cloud {
         self.session.endpoint_filters_mut()
     }
 
+    /// Session used by this cloud.
+    ///
+    /// An escape hatch for calls not covered by this crate. Requests made via the session
+    /// (or its client) are authenticated, and the token is refreshed when needed.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # async fn async_wrapper() -> openstack::Result<()> {
+    /// let os = openstack::Cloud::from_env().await?;
+    /// let url = reqwest::Url::parse("https://cloud.example.com/custom/v1/things")
+    ///     .expect("Invalid URL");
+    /// let response = os
+    ///     .session()
+    ///     .client()
+    ///     .request(reqwest::Method::GET, url)
+    ///     .send()
+    ///     .await?;
+    /// println!("Status is {}", response.status());
+    /// # Ok(()) }
+    /// ```
+    #[inline]
+    pub fn session(&self) -> &Session {
+        &self.session
+    }
+
+    /// Endpoint overrides for this cloud, by service type.
+    #[inline]
+    pub fn endpoint_overrides(&self) -> &HashMap<String, Url> {
+        self.session.endpoint_overrides()
+    }
+
+    /// Use the given endpoint for a service instead of looking it up in the catalog.
+    ///
+    /// The override takes precedence over the service catalog and the endpoint filters.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// async fn cloud_from_env() -> openstack::Result<openstack::Cloud> {
+    ///     let mut cloud = openstack::Cloud::from_env().await?;
+    ///     let url = "http://127.0.0.1:8774/v2.1".parse().expect("Invalid URL");
+    ///     cloud.set_endpoint_override("compute", url);
+    ///     Ok(cloud)
+    /// }
+    /// ```
+    ///
+    /// Removes cached endpoint information and detaches this object from a shared `Session`.
+    pub fn set_endpoint_override<S: Into<String>>(&mut self, service_type: S, url: Url) {
+        let _ = self
+            .session
+            .endpoint_overrides_mut()
+            .insert(service_type.into(), url);
+    }
+
+    /// Convert this cloud into one using the given endpoint for a service.
+    ///
+    /// Removes cached endpoint information and detaches this object from a shared `Session`.
+    #[inline]
+    pub fn with_endpoint_override<S: Into<String>>(mut self, service_type: S, url: Url) -> Cloud {
+        self.set_endpoint_override(service_type, url);
+        self
+    }
+
+    /// Rewrite the endpoints of all supported services with a user-supplied function.
+    ///
+    /// Useful to work around broken service catalogs, e.g. ones advertising `https` endpoints
+    /// that are only reachable over `http`. Existing endpoint overrides are rewritten as well,
+    /// services missing from the catalog are skipped.
+    ///
+    /// The rewrite is applied once, and the results are stored as endpoint overrides. Since
+    /// overrides take precedence over the catalog, changing the region, the endpoint interface
+    /// or the endpoint filters afterwards has no effect on the rewritten services. Configure
+    /// them (and any service type overrides) before calling this method, and call it again
+    /// after setting further endpoint or service type overrides.
+    ///
+    /// Removes cached endpoint information and detaches this object from a shared `Session`.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// async fn cloud_from_env() -> openstack::Result<openstack::Cloud> {
+    ///     let mut cloud = openstack::Cloud::from_env().await?;
+    ///     cloud
+    ///         .rewrite_endpoints(|mut url| {
+    ///             let _ = url.set_scheme("http");
+    ///             url
+    ///         })
+    ///         .await?;
+    ///     Ok(cloud)
+    /// }
+    /// ```
+    pub async fn rewrite_endpoints<F>(&mut self, rewrite: F) -> Result<()>
+    where
+        F: Fn(Url) -> Url,
+    {
+        let mut endpoints: Vec<(String, Url)> = self
+            .endpoint_overrides()
+            .iter()
+            .map(|(service_type, url)| (service_type.clone(), url.clone()))
+            .collect();
+        for catalog_type in SERVICE_TYPES {
+            if self.endpoint_overrides().contains_key(*catalog_type) {
+                continue;
+            }
+            if let Some(url) = self.find_endpoint(catalog_type).await? {
+                endpoints.push((catalog_type.to_string(), url));
+            }
+        }
+
+        for (service_type, endpoint) in endpoints {
+            let rewritten = rewrite(endpoint);
+            debug!("Using endpoint {} for service {}", rewritten, service_type);
+            self.set_endpoint_override(service_type, rewritten);
+        }
+        Ok(())
+    }
+
+    /// Look up an endpoint in the catalog, `None` if the catalog type is not present.
+    async fn find_endpoint(&self, catalog_type: &str) -> Result<Option<Url>> {
+        match self
+            .session
+            .client()
+            .get_endpoint(catalog_type, self.session.endpoint_filters())
+            .await
+        {
+            Ok(url) => Ok(Some(url)),
+            Err(err) if err.kind() == ErrorKind::EndpointNotFound => {
+                debug!("No {} endpoint in the catalog: {}", catalog_type, err);
+                Ok(None)
+            }
+            Err(err) => Err(err),
+        }
+    }
+
     /// Convert this cloud into one using the given endpoint interface.
     ///
     /// # Example
@@ -158,6 +331,26 @@ impl Cloud {
         self
     }
 
+    /// Convert this cloud into one using endpoints from the given region.
+    ///
+    /// Overrides the region from `OS_REGION_NAME` or `clouds.yaml`. Without a region, the
+    /// first matching endpoint in the service catalog is used regardless of its region.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// async fn cloud_from_env() -> openstack::Result<openstack::Cloud> {
+    ///     openstack::Cloud::from_env().await
+    ///         .map(|os| os.with_region("RegionTwo"))
+    /// }
+    /// ```
+    ///
+    /// Removes cached endpoint information and detaches this object from a shared `Session`.
+    pub fn with_region<S: Into<String>>(mut self, region: S) -> Cloud {
+        self.endpoint_filters_mut().set_region(region);
+        self
+    }
+
     /// Convert this cloud into one using the given endpoint filters.
     ///
     /// Removes cached endpoint information and detaches this object from a shared `Session`.
@@ -167,11 +360,169 @@ impl Cloud {
         self
     }
 
+    /// Use the catalog entry of a non-standard service type for a service.
+    ///
+    /// Looks up `catalog_type` (e.g. `computev3`) in the service catalog and uses its endpoint
+    /// for the service normally registered as `service_type` (e.g. `compute`).
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// async fn cloud_from_env() -> openstack::Result<openstack::Cloud> {
+    ///     let mut cloud = openstack::Cloud::from_env().await?;
+    ///     cloud.set_service_type_override("compute", "computev3").await?;
+    ///     Ok(cloud)
+    /// }
+    /// ```
+    ///
+    /// Removes cached endpoint information and detaches this object from a shared `Session`.
+    pub async fn set_service_type_override<S1, S2>(
+        &mut self,
+        service_type: S1,
+        catalog_type: S2,
+    ) -> Result<()>
+    where
+        S1: Into<String>,
+        S2: AsRef<str>,
+    {
+        let endpoint = self
+            .find_endpoint(catalog_type.as_ref())
+            .await?
+            .ok_or_else(|| utils::endpoint_not_found(catalog_type.as_ref()))?;
+        let service_type = service_type.into();
+        debug!(
+            "Using {} endpoint {} for service {}",
+            catalog_type.as_ref(),
+            endpoint,
+            service_type
+        );
+        self.set_endpoint_override(service_type, endpoint);
+        Ok(())
+    }
+
+    /// Use the first of several catalog types found in the service catalog for a service.
+    ///
+    /// Some clouds register the same service under several types (e.g. `compute` and
+    /// `computev21`). The candidates are tried in the given order, and the endpoint of the first
+    /// one present in the catalog is used for `service_type`. Fails with `EndpointNotFound`
+    /// listing the candidates if none of them is present.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// async fn cloud_from_env() -> openstack::Result<openstack::Cloud> {
+    ///     let mut cloud = openstack::Cloud::from_env().await?;
+    ///     cloud
+    ///         .set_preferred_catalog_types("compute", &["computev21", "compute"])
+    ///         .await?;
+    ///     Ok(cloud)
+    /// }
+    /// ```
+    ///
+    /// Removes cached endpoint information and detaches this object from a shared `Session`.
+    pub async fn set_preferred_catalog_types<S>(
+        &mut self,
+        service_type: S,
+        candidates: &[&str],
+    ) -> Result<()>
+    where
+        S: Into<String>,
+    {
+        let service_type = service_type.into();
+        for catalog_type in candidates {
+            if let Some(endpoint) = self.find_endpoint(catalog_type).await? {
+                debug!(
+                    "Using {} endpoint {} for service {}",
+                    catalog_type, endpoint, service_type
+                );
+                self.set_endpoint_override(service_type, endpoint);
+                return Ok(());
+            }
+        }
+        Err(Error::new(
+            ErrorKind::EndpointNotFound,
+            format!(
+                "None of the catalog types [{}] found for service {}",
+                candidates.join(", "),
+                service_type
+            ),
+        ))
+    }
+
+    /// Merge the given metadata into the metadata of several servers.
+    ///
+    /// The servers are updated concurrently. A failure for one server does not stop the others:
+    /// the result is returned for each server ID (in no particular order).
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use std::collections::HashMap;
+    ///
+    /// # async fn async_wrapper() {
+    /// let os = openstack::Cloud::from_env().await.expect("Unable to authenticate");
+    /// let mut metadata = HashMap::new();
+    /// let _ = metadata.insert("deployment".to_string(), "prod".to_string());
+    /// for (id, result) in os.update_servers_metadata(vec!["id1", "id2"], metadata).await {
+    ///     if let Err(err) = result {
+    ///         println!("Failed to update server {}: {}", id, err);
+    ///     }
+    /// }
+    /// # }
+    /// ```
+    #[cfg(feature = "compute")]
+    pub async fn update_servers_metadata<I>(
+        &self,
+        ids: I,
+        metadata: HashMap<String, String>,
+    ) -> Vec<(String, Result<()>)>
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        compute::update_metadata_many(&self.session, ids, metadata).await
+    }
+
+    /// Sum the flavor resources (VCPUs, RAM and root disk) used by the given servers.
+    ///
+    /// Each distinct flavor is only fetched once.
+    #[cfg(feature = "compute")]
+    pub async fn aggregate_server_usage<I>(&self, ids: I) -> Result<ResourceTotals>
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        compute::aggregate_usage(&self.session, ids).await
+    }
+
     /// Refresh this `Cloud` object (renew token, refetch service catalog, etc).
+    ///
+    /// The token is renewed unconditionally, so this can be used to force re-authentication when
+    /// the cached token is known to be stale (e.g. after a role change). It is a no-op when no
+    /// authentication is used.
     pub async fn refresh(&mut self) -> Result<()> {
         self.session.refresh().await
     }
 
+    /// Check that the cloud is reachable and the credentials work.
+    ///
+    /// Authenticates (unless a valid token is cached) and fetches the compute API version
+    /// details, so both the identity service and the compute endpoint are exercised.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # async fn health_check() -> openstack::Result<()> {
+    /// let os = openstack::Cloud::from_env().await?;
+    /// os.health_check().await?;
+    /// # Ok(()) }
+    /// ```
+    #[cfg(feature = "compute")]
+    pub async fn health_check(&self) -> Result<()> {
+        let _ = compute::get_version_details(&self.session).await?;
+        Ok(())
+    }
+
     /// Create a new container.
     ///
     /// If the container already exists, this call returns successfully.
@@ -249,6 +600,15 @@ impl Cloud {
         KeyPairQuery::new(self.session.clone())
     }
 
+    /// Build a query against the deployment-wide migration list.
+    ///
+    /// The returned object is a builder that should be used to construct
+    /// the query. Requires admin privileges.
+    #[cfg(feature = "compute")]
+    pub fn find_migrations(&self) -> MigrationQuery {
+        MigrationQuery::new(self.session.clone())
+    }
+
     /// Build a query against network list.
     ///
     /// The returned object is a builder that should be used to construct
@@ -267,6 +627,15 @@ impl Cloud {
         PortQuery::new(self.session.clone())
     }
 
+    /// Build a query against RBAC policy list.
+    ///
+    /// The returned object is a builder that should be used to construct
+    /// the query.
+    #[cfg(feature = "network")]
+    pub fn find_rbac_policies(&self) -> RbacPolicyQuery {
+        RbacPolicyQuery::new(self.session.clone())
+    }
+
     /// Build a query against router list.
     ///
     /// The returned object is a builder that should be used to construct
@@ -310,6 +679,15 @@ impl Cloud {
         SubnetQuery::new(self.session.clone())
     }
 
+    /// Build a query against subnet pool list.
+    ///
+    /// The returned object is a builder that should be used to construct
+    /// the query.
+    #[cfg(feature = "network")]
+    pub fn find_subnet_pools(&self) -> SubnetPoolQuery {
+        SubnetPoolQuery::new(self.session.clone())
+    }
+
     /// Get object container metadata by its name.
     ///
     /// # Example
@@ -348,6 +726,85 @@ impl Cloud {
         Object::load(self.session.clone(), container, name).await
     }
 
+    /// Get details of the compute API version in use.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use openstack;
+    ///
+    /// # async fn async_wrapper() {
+    /// let os = openstack::Cloud::from_env().await.expect("Unable to authenticate");
+    /// let details = os.get_compute_version_details().await.expect("Unable to get the version");
+    /// println!("Maximum microversion is {:?}", details.version);
+    /// # }
+    /// ```
+    #[cfg(feature = "compute")]
+    pub async fn get_compute_version_details(&self) -> Result<VersionDetails> {
+        compute::get_version_details(&self.session).await
+    }
+
+    /// Get aggregated resource statistics of all hypervisors.
+    ///
+    /// Only available to administrators. The Compute service removed this call in API
+    /// version 2.88, but it is still served when an older version is requested.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # async fn async_wrapper() {
+    /// let os = openstack::Cloud::from_env().await.expect("Unable to authenticate");
+    /// let stats = os.get_hypervisor_statistics().await.expect("Unable to get statistics");
+    /// println!("{} of {} VCPUs used", stats.vcpus_used, stats.vcpus);
+    /// # }
+    /// ```
+    #[cfg(feature = "compute")]
+    pub async fn get_hypervisor_statistics(&self) -> Result<HypervisorStatistics> {
+        compute::get_hypervisor_statistics(&self.session).await
+    }
+
+    /// Get resource usage of a project within the given period.
+    ///
+    /// Includes the usage of each server. Getting usage of other projects requires
+    /// administrator privileges.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # async fn async_wrapper() {
+    /// use chrono::{Duration, Utc};
+    ///
+    /// let os = openstack::Cloud::from_env().await.expect("Unable to authenticate");
+    /// let end = Utc::now();
+    /// let usage = os
+    ///     .get_tenant_usage("8a1c355b2e1e440a8aa8f272df72bc32", end - Duration::days(30), end)
+    ///     .await
+    ///     .expect("Unable to get usage");
+    /// println!("{} VCPU-hours used", usage.total_vcpus_usage);
+    /// # }
+    /// ```
+    #[cfg(feature = "compute")]
+    pub async fn get_tenant_usage<Id: AsRef<str>>(
+        &self,
+        project_id: Id,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<TenantUsage> {
+        compute::get_tenant_usage(&self.session, project_id, start, end).await
+    }
+
+    /// List resource usage of all projects within the given period.
+    ///
+    /// Only available to administrators. Includes the usage of each server.
+    #[cfg(feature = "compute")]
+    pub async fn list_tenant_usages(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<Vec<TenantUsage>> {
+        compute::list_tenant_usages(&self.session, start, end).await
+    }
+
     /// Find a flavor by its name or ID.
     ///
     /// # Example
@@ -418,6 +875,29 @@ impl Cloud {
         KeyPair::new(self.session.clone(), name).await
     }
 
+    /// Check whether a key pair with the given name exists.
+    ///
+    /// Errors other than `ResourceNotFound` are returned as they are.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # async fn async_wrapper() {
+    /// let os = openstack::Cloud::from_env().await.expect("Unable to authenticate");
+    /// if !os.keypair_exists("default").await.expect("Unable to check the key pair") {
+    ///     panic!("Key pair default is missing");
+    /// }
+    /// # }
+    /// ```
+    #[cfg(feature = "compute")]
+    pub async fn keypair_exists<Id: AsRef<str>>(&self, name: Id) -> Result<bool> {
+        match KeyPair::new(self.session.clone(), name).await {
+            Ok(_) => Ok(true),
+            Err(err) if err.kind() == ErrorKind::ResourceNotFound => Ok(false),
+            Err(err) => Err(err),
+        }
+    }
+
     /// Find an network by its name or ID.
     ///
     /// # Example
@@ -454,6 +934,45 @@ impl Cloud {
         Port::load(self.session.clone(), id_or_name).await
     }
 
+    /// Get a quota class (e.g. `default`).
+    ///
+    /// Quota classes define the quotas of projects without explicit quotas. Requires admin
+    /// privileges.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # async fn async_wrapper() {
+    /// let os = openstack::Cloud::from_env().await.expect("Unable to authenticate");
+    /// let mut quota = os.get_quota_class("default").await.expect("Unable to get quotas");
+    /// quota.set_instances(50);
+    /// quota.save().await.expect("Unable to update quotas");
+    /// # }
+    /// ```
+    #[cfg(feature = "compute")]
+    pub async fn get_quota_class<S: Into<String>>(&self, name: S) -> Result<QuotaClass> {
+        QuotaClass::load(self.session.clone(), name).await
+    }
+
+    /// Find an RBAC policy by its ID.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use openstack;
+    ///
+    /// # async fn async_wrapper() {
+    /// let os = openstack::Cloud::from_env().await.expect("Unable to authenticate");
+    /// let policy = os.get_rbac_policy("6a2d1ab2-2b8f-4bd5-9d4c-43d3e15e2b1e")
+    ///     .await
+    ///     .expect("Unable to get an RBAC policy");
+    /// # }
+    /// ```
+    #[cfg(feature = "network")]
+    pub async fn get_rbac_policy<Id: AsRef<str>>(&self, id: Id) -> Result<RbacPolicy> {
+        RbacPolicy::load(self.session.clone(), id).await
+    }
+
     /// Find a router by its name or ID.
     ///
     /// # Example
@@ -490,6 +1009,52 @@ impl Cloud {
         Server::load(self.session.clone(), id_or_name).await
     }
 
+    /// Find a server by its ID and return it together with the raw response body.
+    ///
+    /// Intended for debugging differences between the cloud's responses and this crate.
+    /// If the body does not match the expected format, the error message contains it.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use openstack;
+    ///
+    /// # async fn async_wrapper() {
+    /// let os = openstack::Cloud::from_env().await.expect("Unable to authenticate");
+    /// let (server, body) = os.get_server_raw("8a1c355b-2e1e-440a-8aa8-f272df72bc32")
+    ///     .await
+    ///     .expect("Unable to get a server");
+    /// println!("{} was received as {}", server.id(), body["server"]);
+    /// # }
+    /// ```
+    #[cfg(feature = "compute")]
+    pub async fn get_server_raw<Id: AsRef<str>>(
+        &self,
+        id: Id,
+    ) -> Result<(Server, serde_json::Value)> {
+        Server::load_raw(self.session.clone(), id).await
+    }
+
+    /// Find a server group by its ID.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use openstack;
+    ///
+    /// # async fn async_wrapper() {
+    /// let os = openstack::Cloud::from_env().await.expect("Unable to authenticate");
+    /// let group = os.get_server_group("5bbcc3c4-1da2-4437-a48a-66f15b1b13f9")
+    ///     .await
+    ///     .expect("Unable to get a server group");
+    /// let members = group.member_servers().await.expect("Unable to get members");
+    /// # }
+    /// ```
+    #[cfg(feature = "compute")]
+    pub async fn get_server_group<Id: AsRef<str>>(&self, id: Id) -> Result<ServerGroup> {
+        ServerGroup::load(self.session.clone(), id).await
+    }
+
     /// Find an subnet by its name or ID.
     ///
     /// # Example
@@ -509,6 +1074,25 @@ impl Cloud {
         Subnet::load(self.session.clone(), id_or_name).await
     }
 
+    /// Find a subnet pool by its name or ID.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use openstack;
+    ///
+    /// # async fn async_wrapper() {
+    /// let os = openstack::Cloud::from_env().await.expect("Unable to authenticate");
+    /// let pool = os.get_subnet_pool("shared-pool")
+    ///     .await
+    ///     .expect("Unable to get a subnet pool");
+    /// # }
+    /// ```
+    #[cfg(feature = "network")]
+    pub async fn get_subnet_pool<Id: AsRef<str>>(&self, id_or_name: Id) -> Result<SubnetPool> {
+        SubnetPool::load(self.session.clone(), id_or_name).await
+    }
+
     /// List all containers.
     ///
     /// This call can yield a lot of results, use the
@@ -634,6 +1218,25 @@ impl Cloud {
         self.find_keypairs().all().await
     }
 
+    /// List all migrations across the deployment.
+    ///
+    /// Requires admin privileges.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use openstack;
+    ///
+    /// # async fn async_wrapper() {
+    /// let os = openstack::Cloud::from_env().await.expect("Unable to authenticate");
+    /// let result = os.list_migrations().await.expect("Unable to fetch migrations");
+    /// # }
+    /// ```
+    #[cfg(feature = "compute")]
+    pub async fn list_migrations(&self) -> Result<Vec<Migration>> {
+        self.find_migrations().all().await
+    }
+
     /// List all networks.
     ///
     /// This call can yield a lot of results, use the
@@ -718,6 +1321,23 @@ impl Cloud {
         self.find_servers().all().await
     }
 
+    /// List all server groups.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use openstack;
+    ///
+    /// # async fn async_wrapper() {
+    /// let os = openstack::Cloud::from_env().await.expect("Unable to authenticate");
+    /// let groups = os.list_server_groups().await.expect("Unable to fetch server groups");
+    /// # }
+    /// ```
+    #[cfg(feature = "compute")]
+    pub async fn list_server_groups(&self) -> Result<Vec<ServerGroup>> {
+        ServerGroup::list(self.session.clone()).await
+    }
+
     /// List all subnets.
     ///
     /// This call can yield a lot of results, use the
@@ -798,6 +1418,54 @@ impl Cloud {
         NewPort::new(self.session.clone(), network.into())
     }
 
+    /// Prepare a new RBAC policy for creation.
+    ///
+    /// This call returns a `NewRbacPolicy` object, which is a builder to populate
+    /// RBAC policy fields.
+    ///
+    /// # Example
+    ///
+    /// Share a network with another project:
+    ///
+    /// ```rust,no_run
+    /// use openstack::network::{RbacAction, RbacObjectType};
+    ///
+    /// # async fn async_wrapper() {
+    /// let os = openstack::Cloud::from_env().await.expect("Unable to authenticate");
+    /// let network = os.get_network("private").await.expect("Unable to get a network");
+    /// let policy = os
+    ///     .new_rbac_policy(
+    ///         RbacObjectType::Network,
+    ///         network.id().clone(),
+    ///         "c3f5d5b1e2a04f3b8e6f0d2a9b7c4e1f",
+    ///         RbacAction::AccessAsShared,
+    ///     )
+    ///     .create()
+    ///     .await
+    ///     .expect("Unable to share the network");
+    /// # }
+    /// ```
+    #[cfg(feature = "network")]
+    pub fn new_rbac_policy<S1, S2>(
+        &self,
+        object_type: RbacObjectType,
+        object_id: S1,
+        target_tenant: S2,
+        action: RbacAction,
+    ) -> NewRbacPolicy
+    where
+        S1: Into<String>,
+        S2: Into<String>,
+    {
+        NewRbacPolicy::new(
+            self.session.clone(),
+            object_type,
+            object_id.into(),
+            target_tenant.into(),
+            action,
+        )
+    }
+
     /// Prepare a new router for creation.
     ///
     /// This call returns a `NewRouter` object, which is a builder to populate
@@ -820,6 +1488,34 @@ impl Cloud {
         NewServer::new(self.session.clone(), name.into(), flavor.into())
     }
 
+    /// Prepare a new server group for creation.
+    ///
+    /// This call returns a `NewServerGroup` object, which is a builder to
+    /// populate server group fields.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use openstack;
+    ///
+    /// # async fn async_wrapper() {
+    /// let os = openstack::Cloud::from_env().await.expect("Unable to authenticate");
+    /// let group = os
+    ///     .new_server_group("spread", "anti-affinity")
+    ///     .create()
+    ///     .await
+    ///     .expect("Unable to create a server group");
+    /// # }
+    /// ```
+    #[cfg(feature = "compute")]
+    pub fn new_server_group<S1, S2>(&self, name: S1, policy: S2) -> NewServerGroup
+    where
+        S1: Into<String>,
+        S2: Into<String>,
+    {
+        NewServerGroup::new(self.session.clone(), name.into(), policy.into())
+    }
+
     /// Prepare a new subnet for creation.
     ///
     /// This call returns a `NewSubnet` object, which is a builder to populate
@@ -846,7 +1542,58 @@ impl Cloud {
     where
         N: Into<NetworkRef>,
     {
-        NewSubnet::new(self.session.clone(), network.into(), cidr)
+        NewSubnet::new(self.session.clone(), network.into(), Some(cidr))
+    }
+
+    /// Prepare a new subnet allocated from a subnet pool for creation.
+    ///
+    /// The CIDR is allocated from the pool (given by name or ID), using its default prefix
+    /// length unless `with_prefixlen` is used.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # async fn async_wrapper() {
+    /// let os = openstack::Cloud::from_env().await.expect("Unable to authenticate");
+    /// let new_subnet = os.new_subnet_from_pool("private-net", "private-pool")
+    ///     .with_prefixlen(26)
+    ///     .create().await.expect("Unable to create subnet");
+    /// # }
+    /// ```
+    #[cfg(feature = "network")]
+    pub fn new_subnet_from_pool<N, S>(&self, network: N, subnet_pool: S) -> NewSubnet
+    where
+        N: Into<NetworkRef>,
+        S: Into<String>,
+    {
+        NewSubnet::new(self.session.clone(), network.into(), None).with_subnetpool_id(subnet_pool)
+    }
+
+    /// Prepare a new subnet pool for creation.
+    ///
+    /// This call returns a `NewSubnetPool` object, which is a builder to populate
+    /// subnet pool fields.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// extern crate ipnet;
+    /// extern crate openstack;
+    ///
+    /// # async fn async_wrapper() {
+    /// let os = openstack::Cloud::from_env().await.expect("Unable to authenticate");
+    /// let prefix = "10.0.0.0/16".parse().unwrap();
+    /// let pool = os.new_subnet_pool("private-pool", vec![prefix])
+    ///     .with_default_prefixlen(24)
+    ///     .create().await.expect("Unable to create subnet pool");
+    /// # }
+    /// ```
+    #[cfg(feature = "network")]
+    pub fn new_subnet_pool<S>(&self, name: S, prefixes: Vec<ipnet::IpNet>) -> NewSubnetPool
+    where
+        S: Into<String>,
+    {
+        NewSubnetPool::new(self.session.clone(), name.into(), prefixes)
     }
 }
 
@@ -855,3 +1602,128 @@ impl From<Session> for Cloud {
         Cloud { session: value }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use osauth::NoAuth;
+    use reqwest::Url;
+
+    use super::super::ErrorKind;
+    use super::{Cloud, SERVICE_TYPES};
+
+    #[tokio::test]
+    async fn test_service_type_override() {
+        let auth = NoAuth::new("http://127.0.0.1:8774/v2.1").unwrap();
+        let mut cloud = Cloud::new(auth).await.unwrap();
+        cloud
+            .set_service_type_override("compute", "computev3")
+            .await
+            .unwrap();
+        let overrides = cloud.endpoint_overrides();
+        assert_eq!(
+            overrides.get("compute").map(|url| url.as_str()),
+            Some("http://127.0.0.1:8774/v2.1")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_with_region() {
+        let auth = NoAuth::new("http://127.0.0.1:8774/v2.1").unwrap();
+        let cloud = Cloud::new(auth).await.unwrap();
+        assert_eq!(cloud.endpoint_filters().region, None);
+        let cloud = cloud.with_region("RegionTwo");
+        assert_eq!(
+            cloud.endpoint_filters().region.as_deref(),
+            Some("RegionTwo")
+        );
+    }
+
+    #[cfg(feature = "compute")]
+    #[tokio::test]
+    async fn test_keypair_exists_other_errors() {
+        // Nothing listens on this port, the connection error must not become `false`.
+        let auth = NoAuth::new("http://127.0.0.1:1/v2.1").unwrap();
+        let cloud = Cloud::new(auth).await.unwrap();
+        let err = cloud.keypair_exists("default").await.unwrap_err();
+        assert_ne!(err.kind(), ErrorKind::ResourceNotFound);
+    }
+
+    #[tokio::test]
+    async fn test_endpoint_override() {
+        let auth = NoAuth::new("http://127.0.0.1:8774/v2.1").unwrap();
+        let url: Url = "http://127.0.0.1:9696/".parse().unwrap();
+        let cloud = Cloud::new(auth)
+            .await
+            .unwrap()
+            .with_endpoint_override("network", url.clone());
+        assert_eq!(cloud.endpoint_overrides().get("network"), Some(&url));
+    }
+
+    #[tokio::test]
+    async fn test_preferred_catalog_types() {
+        let auth = NoAuth::new("http://127.0.0.1:8774/v2.1").unwrap();
+        let mut cloud = Cloud::new(auth).await.unwrap();
+        cloud
+            .set_preferred_catalog_types("compute", &["computev21", "compute"])
+            .await
+            .unwrap();
+        assert!(cloud.endpoint_overrides().contains_key("compute"));
+
+        let err = cloud
+            .set_preferred_catalog_types("network", &[])
+            .await
+            .err()
+            .unwrap();
+        assert_eq!(err.kind(), ErrorKind::EndpointNotFound);
+        assert!(!cloud.endpoint_overrides().contains_key("network"));
+    }
+
+    #[tokio::test]
+    async fn test_rewrite_endpoints() {
+        let auth = NoAuth::new("https://127.0.0.1:8774/v2.1").unwrap();
+        let mut cloud = Cloud::new(auth).await.unwrap();
+        cloud
+            .rewrite_endpoints(|mut url| {
+                url.set_scheme("http").unwrap();
+                url
+            })
+            .await
+            .unwrap();
+        for service_type in SERVICE_TYPES {
+            assert_eq!(
+                cloud
+                    .endpoint_overrides()
+                    .get(*service_type)
+                    .map(|url| url.as_str()),
+                Some("http://127.0.0.1:8774/v2.1")
+            );
+        }
+
+        // Overrides set later are picked up when the rewrite is run again, including ones
+        // for services this crate has no support for.
+        cloud.set_endpoint_override("placement", "https://127.0.0.1:8778/".parse().unwrap());
+        cloud
+            .rewrite_endpoints(|mut url| {
+                url.set_scheme("http").unwrap();
+                url
+            })
+            .await
+            .unwrap();
+        assert_eq!(
+            cloud
+                .endpoint_overrides()
+                .get("placement")
+                .map(|url| url.as_str()),
+            Some("http://127.0.0.1:8778/")
+        );
+        for service_type in SERVICE_TYPES {
+            assert_eq!(
+                cloud
+                    .endpoint_overrides()
+                    .get(*service_type)
+                    .map(|url| url.as_str()),
+                Some("http://127.0.0.1:8774/v2.1")
+            );
+        }
+    }
+}
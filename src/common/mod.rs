@@ -18,9 +18,10 @@ pub(crate) mod protocol;
 mod resourceiterator;
 mod types;
 
+pub use osauth::common::Link;
 pub use osauth::ApiVersion;
 
-pub use self::resourceiterator::{ResourceIterator, ResourceQuery};
+pub use self::resourceiterator::{Page, ResourceIterator, ResourceQuery};
 pub use self::types::{
     ContainerRef, FlavorRef, ImageRef, KeyPairRef, NetworkRef, ObjectRef, PortRef, ProjectRef,
     Refresh, RouterRef, SecurityGroupRef, SnapshotRef, SubnetRef, UserRef, VolumeRef,
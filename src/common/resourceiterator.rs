@@ -14,12 +14,10 @@
 
 //! Generic API bits for implementing new services.
 
-use std::vec;
-
 use async_stream::try_stream;
 use async_trait::async_trait;
 use futures::pin_mut;
-use futures::stream::{Stream, TryStreamExt};
+use futures::stream::{self, Stream, TryStreamExt};
 
 use super::super::{Error, ErrorKind, Result};
 
@@ -37,6 +35,11 @@ pub trait ResourceQuery {
     /// Whether pagination is supported for this query.
     async fn can_paginate(&self) -> Result<bool>;
 
+    /// Number of resources to request per page when paginating.
+    fn page_size(&self) -> usize {
+        Self::DEFAULT_LIMIT
+    }
+
     /// Extract a marker from a resource.
     fn extract_marker(&self, resource: &Self::Item) -> String;
 
@@ -56,11 +59,27 @@ pub trait ResourceQuery {
     }
 }
 
+/// A single page of resources.
+#[derive(Debug, Clone)]
+pub struct Page<T> {
+    /// Resources on this page.
+    pub items: Vec<T>,
+    /// Marker to request the next page with, `None` if this is the last page.
+    pub next_marker: Option<String>,
+}
+
+impl<T> Page<T> {
+    /// Whether more results are available after this page.
+    #[inline]
+    pub fn has_more(&self) -> bool {
+        self.next_marker.is_some()
+    }
+}
+
 /// Generic iterator over resources.
 #[derive(Debug, Clone)]
 pub struct ResourceIterator<Q: ResourceQuery> {
     query: Q,
-    cache: Option<vec::IntoIter<Q::Item>>,
     marker: Option<String>,
     can_paginate: Option<bool>,
     validated: bool,
@@ -74,7 +93,6 @@ where
     pub(crate) fn new(query: Q) -> ResourceIterator<Q> {
         ResourceIterator {
             query,
-            cache: None,
             marker: None,
             can_paginate: None, // ask the service later
             validated: false,
@@ -111,13 +129,60 @@ where
         }
     }
 
+    /// Fetch one page of at most `limit` resources starting after `marker`.
+    ///
+    /// One extra resource is requested to find out whether there is a next page. Fails with
+    /// `InvalidInput` if `limit` is zero or if the query cannot be paginated (e.g. because
+    /// a limit or a marker was already set on it).
+    pub async fn fetch_page(
+        mut self,
+        limit: usize,
+        marker: Option<String>,
+    ) -> Result<Page<Q::Item>> {
+        if limit == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Page limit must be positive",
+            ));
+        }
+
+        if !self.validated {
+            self.query.validate().await?;
+        }
+
+        if !self.query.can_paginate().await? {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Pagination is not supported for this query",
+            ));
+        }
+
+        let mut items = self.query.fetch_chunk(Some(limit + 1), marker).await?;
+        let next_marker = if items.len() > limit {
+            items.truncate(limit);
+            items.last().map(|item| self.query.extract_marker(item))
+        } else {
+            None
+        };
+        Ok(Page { items, next_marker })
+    }
+
     /// Convert this iterator into a proper implementor of the `Stream` trait.
     ///
     /// This stream yields `Result<Q::Item>` items and is therefore also an
     /// implementor of the `TryStream` trait.
     ///
     /// Note that no requests are done until you start iterating.
-    pub fn into_stream(mut self) -> impl Stream<Item = Result<Q::Item>> {
+    pub fn into_stream(self) -> impl Stream<Item = Result<Q::Item>> {
+        self.into_page_stream()
+            .map_ok(|page| stream::iter(page.into_iter().map(Ok)))
+            .try_flatten()
+    }
+
+    /// Convert this iterator into a stream of pages as returned by the service.
+    ///
+    /// Empty pages are never yielded.
+    pub(crate) fn into_page_stream(mut self) -> impl Stream<Item = Result<Vec<Q::Item>>> {
         try_stream! {
             if !self.validated {
                 self.query.validate().await?;
@@ -129,30 +194,25 @@ where
             }
 
             loop {
-                let maybe_next = self.cache.as_mut().and_then(|cache| cache.next());
-                if let Some(next) = maybe_next {
-                    self.marker = Some(self.query.extract_marker(&next));
-                    yield next;
-                } else if self.cache.is_some() && self.can_paginate == Some(false) {
+                let (marker, limit) = if self.can_paginate == Some(true) {
+                    // can_paginate=true implies no limit was provided
+                    (self.marker.clone(), Some(self.query.page_size()))
+                } else {
+                    (None, None)
+                };
+
+                // Services may return fewer items than requested even when more are
+                // available, so only an empty page marks the end.
+                let page = self.query.fetch_chunk(limit, marker).await?;
+                match page.last() {
+                    Some(last) => self.marker = Some(self.query.extract_marker(last)),
+                    None => break,
+                }
+                yield page;
+
+                if self.can_paginate == Some(false) {
                     // We have exhausted the results and pagination is not possible
                     break;
-                } else {
-                    let (marker, limit) = if self.can_paginate == Some(true) {
-                        // can_paginate=true implies no limit was provided
-                        (self.marker.clone(), Some(Q::DEFAULT_LIMIT))
-                    } else {
-                        (None, None)
-                    };
-
-                    let mut iter = self.query.fetch_chunk(limit, marker).await?.into_iter();
-                    let maybe_next = iter.next();
-                    self.cache = Some(iter);
-                    if let Some(next) = maybe_next {
-                        self.marker = Some(self.query.extract_marker(&next));
-                        yield next;
-                    } else {
-                        break;
-                    }
                 }
             }
         }
@@ -164,7 +224,7 @@ mod test {
     use async_trait::async_trait;
     use futures::stream::TryStreamExt;
 
-    use super::super::super::Result;
+    use super::super::super::{ErrorKind, Result};
     use super::{ResourceIterator, ResourceQuery};
 
     #[derive(Debug, PartialEq, Eq)]
@@ -202,6 +262,45 @@ mod test {
         }
     }
 
+    #[derive(Debug)]
+    struct ShortPage {
+        page_size: usize,
+    }
+
+    #[async_trait]
+    impl ResourceQuery for ShortPage {
+        type Item = Test;
+
+        const DEFAULT_LIMIT: usize = 2;
+
+        async fn can_paginate(&self) -> Result<bool> {
+            Ok(true)
+        }
+
+        fn page_size(&self) -> usize {
+            self.page_size
+        }
+
+        fn extract_marker(&self, resource: &Test) -> String {
+            resource.0.to_string()
+        }
+
+        async fn fetch_chunk(
+            &self,
+            limit: Option<usize>,
+            marker: Option<String>,
+        ) -> Result<Vec<Self::Item>> {
+            assert_eq!(limit, Some(3));
+            // The service caps pages at 2 items, shorter than requested.
+            Ok(match marker.map(|s| s.parse::<u8>().unwrap()) {
+                None => vec![Test(0), Test(1)],
+                Some(1) => vec![Test(2)],
+                Some(2) => Vec::new(),
+                Some(x) => panic!("unexpected marker {:?}", x),
+            })
+        }
+    }
+
     #[derive(Debug)]
     struct NoPagination;
 
@@ -239,6 +338,36 @@ mod test {
         );
     }
 
+    #[tokio::test]
+    async fn test_resource_iterator_pages() {
+        let it = ResourceIterator::new(ShortPage { page_size: 3 });
+        assert_eq!(
+            it.into_page_stream()
+                .try_collect::<Vec<Vec<Test>>>()
+                .await
+                .unwrap(),
+            vec![vec![Test(0), Test(1)], vec![Test(2)]]
+        );
+
+        let it = ResourceIterator::new(NoPagination);
+        assert_eq!(
+            it.into_page_stream()
+                .try_collect::<Vec<Vec<Test>>>()
+                .await
+                .unwrap(),
+            vec![vec![Test(0), Test(1), Test(2)]]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_resource_iterator_short_pages() {
+        let it = ResourceIterator::new(ShortPage { page_size: 3 });
+        assert_eq!(
+            it.into_stream().try_collect::<Vec<Test>>().await.unwrap(),
+            vec![Test(0), Test(1), Test(2)]
+        );
+    }
+
     #[tokio::test]
     async fn test_resource_iterator_no_pagination() {
         let it: ResourceIterator<NoPagination> = ResourceIterator::new(NoPagination);
@@ -247,4 +376,34 @@ mod test {
             vec![Test(0), Test(1), Test(2)]
         );
     }
+
+    #[tokio::test]
+    async fn test_resource_iterator_fetch_page() {
+        let page = ResourceIterator::new(TestQuery)
+            .fetch_page(1, None)
+            .await
+            .unwrap();
+        assert_eq!(page.items, vec![Test(0)]);
+        assert_eq!(page.next_marker.as_deref(), Some("0"));
+        assert!(page.has_more());
+
+        let page = ResourceIterator::new(TestQuery)
+            .fetch_page(1, Some("3".into()))
+            .await
+            .unwrap();
+        assert!(page.items.is_empty());
+        assert!(!page.has_more());
+
+        let err = ResourceIterator::new(NoPagination)
+            .fetch_page(1, None)
+            .await
+            .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput);
+
+        let err = ResourceIterator::new(TestQuery)
+            .fetch_page(0, None)
+            .await
+            .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    }
 }
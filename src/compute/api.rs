@@ -17,9 +17,12 @@
 use std::collections::HashMap;
 use std::fmt::Debug;
 
+use chrono::{DateTime, Utc};
+use osauth::client::NO_PATH;
 use osauth::common::{IdAndName, Ref};
-use osauth::services::COMPUTE;
-use osauth::ErrorKind;
+use osauth::services::{ComputeService, COMPUTE};
+use osauth::{Error, ErrorKind, ServiceRequestBuilder};
+use serde::de::DeserializeOwned;
 use serde::Serialize;
 
 use super::super::common::ApiVersion;
@@ -29,10 +32,18 @@ use super::super::Result;
 use super::protocol::*;
 
 const API_VERSION_KEYPAIR_TYPE: ApiVersion = ApiVersion(2, 2);
+const API_VERSION_REMOTE_CONSOLES: ApiVersion = ApiVersion(2, 6);
 const API_VERSION_SERVER_DESCRIPTION: ApiVersion = ApiVersion(2, 19);
+const API_VERSION_SERVER_MIGRATIONS: ApiVersion = ApiVersion(2, 23);
+const API_VERSION_ABORT_MIGRATION: ApiVersion = ApiVersion(2, 24);
+pub const API_VERSION_SERVER_TAGS: ApiVersion = ApiVersion(2, 26);
 const API_VERSION_KEYPAIR_PAGINATION: ApiVersion = ApiVersion(2, 35);
 const API_VERSION_FLAVOR_DESCRIPTION: ApiVersion = ApiVersion(2, 55);
 const API_VERSION_FLAVOR_EXTRA_SPECS: ApiVersion = ApiVersion(2, 61);
+const API_VERSION_TRUSTED_CERTIFICATES: ApiVersion = ApiVersion(2, 63);
+const API_VERSION_SERVER_GROUP_POLICY: ApiVersion = ApiVersion(2, 64);
+const API_VERSION_SERVER_HOST: ApiVersion = ApiVersion(2, 74);
+const API_VERSION_DELETE_ON_TERMINATION: ApiVersion = ApiVersion(2, 79);
 
 async fn flavor_api_version(session: &Session) -> Result<Option<ApiVersion>> {
     session
@@ -46,6 +57,35 @@ async fn flavor_api_version(session: &Session) -> Result<Option<ApiVersion>> {
         .await
 }
 
+/// Attach a volume to a server.
+pub async fn attach_volume<S: AsRef<str>>(
+    session: &Session,
+    id: S,
+    request: VolumeAttachmentCreate,
+) -> Result<VolumeAttachment> {
+    debug!("Attaching volume to server {}: {:?}", id.as_ref(), request);
+    let mut builder = session.post(COMPUTE, &["servers", id.as_ref(), "os-volume_attachments"]);
+    if request.delete_on_termination.is_some() {
+        ensure_api_version(
+            session,
+            API_VERSION_DELETE_ON_TERMINATION,
+            "Setting delete_on_termination",
+        )
+        .await?;
+        builder.set_api_version(API_VERSION_DELETE_ON_TERMINATION);
+    }
+    let body = VolumeAttachmentCreateRoot {
+        volume_attachment: request,
+    };
+    let root: VolumeAttachmentRoot = builder.json(&body).fetch().await?;
+    debug!(
+        "Attached volume {} to server {}",
+        root.volume_attachment.volume_id,
+        id.as_ref()
+    );
+    Ok(root.volume_attachment)
+}
+
 /// Create a key pair.
 pub async fn create_keypair(session: &Session, request: KeyPairCreate) -> Result<KeyPair> {
     let version = if request.key_type.is_some() {
@@ -67,19 +107,99 @@ pub async fn create_keypair(session: &Session, request: KeyPairCreate) -> Result
     Ok(root.keypair)
 }
 
+/// Post a server creation request, picking the API version it requires.
+async fn post_server_create<T>(session: &Session, request: ServerCreate) -> Result<T>
+where
+    T: DeserializeOwned + Send,
+{
+    let version = if request.host.is_some() || request.hypervisor_hostname.is_some() {
+        ensure_api_version(
+            session,
+            API_VERSION_SERVER_HOST,
+            "Requesting a specific host",
+        )
+        .await?;
+        Some(API_VERSION_SERVER_HOST)
+    } else if !request.trusted_image_certificates.is_empty() {
+        ensure_api_version(
+            session,
+            API_VERSION_TRUSTED_CERTIFICATES,
+            "Trusted image certificates",
+        )
+        .await?;
+        Some(API_VERSION_TRUSTED_CERTIFICATES)
+    } else {
+        None
+    };
+    let body = ServerCreateRoot { server: request };
+    let mut builder = session.post(COMPUTE, &["servers"]).json(&body);
+    if let Some(version) = version {
+        builder.set_api_version(version);
+    }
+    builder.fetch().await
+}
+
 /// Create a server.
 pub async fn create_server(session: &Session, request: ServerCreate) -> Result<Ref> {
     debug!("Creating a server with {:?}", request);
-    let body = ServerCreateRoot { server: request };
-    let root: CreatedServerRoot = session
-        .post(COMPUTE, &["servers"])
-        .json(&body)
-        .fetch()
-        .await?;
+    let root: CreatedServerRoot = post_server_create(session, request).await?;
     trace!("Requested creation of server {:?}", root.server);
     Ok(root.server)
 }
 
+/// Create several identical servers, returning their reservation ID.
+pub async fn create_servers(
+    session: &Session,
+    mut request: ServerCreate,
+    count: u32,
+) -> Result<String> {
+    debug!("Creating {} servers with {:?}", count, request);
+    request.min_count = Some(count);
+    request.max_count = Some(count);
+    request.return_reservation_id = Some(true);
+    let root: ReservationRoot = post_server_create(session, request).await?;
+    trace!(
+        "Requested creation of {} servers with reservation {}",
+        count,
+        root.reservation_id
+    );
+    Ok(root.reservation_id)
+}
+
+/// Create a server group.
+///
+/// The policy is sent as `policy` when the cloud supports API version 2.64 and as
+/// a single-item `policies` list otherwise.
+pub async fn create_server_group(
+    session: &Session,
+    request: ServerGroupCreate,
+) -> Result<ServerGroup> {
+    debug!("Creating a server group with {:?}", request);
+    let maybe_version = session
+        .pick_api_version(COMPUTE, Some(API_VERSION_SERVER_GROUP_POLICY))
+        .await?;
+    let body = server_group_create_body(request, maybe_version.is_some());
+    let mut builder = session.post(COMPUTE, &["os-server-groups"]).json(&body);
+    if let Some(version) = maybe_version {
+        builder.set_api_version(version);
+    }
+    let root: ServerGroupRoot = builder.fetch().await?;
+    debug!("Created server group {:?}", root.server_group);
+    Ok(root.server_group)
+}
+
+fn server_group_create_body(
+    mut request: ServerGroupCreate,
+    use_policy: bool,
+) -> ServerGroupCreateRoot {
+    if !use_policy {
+        request.policies = request.policy.take().into_iter().collect();
+    }
+    ServerGroupCreateRoot {
+        server_group: request,
+    }
+}
+
 /// Delete a key pair.
 pub async fn delete_keypair<S: AsRef<str>>(session: &Session, name: S) -> Result<()> {
     debug!("Deleting key pair {}", name.as_ref());
@@ -91,6 +211,51 @@ pub async fn delete_keypair<S: AsRef<str>>(session: &Session, name: S) -> Result
     Ok(())
 }
 
+/// Delete a server group.
+pub async fn delete_server_group<S: AsRef<str>>(session: &Session, id: S) -> Result<()> {
+    debug!("Deleting server group {}", id.as_ref());
+    let _ = session
+        .delete(COMPUTE, &["os-server-groups", id.as_ref()])
+        .send()
+        .await?;
+    debug!("Server group {} was deleted", id.as_ref());
+    Ok(())
+}
+
+/// Abort an in-progress live migration of a server.
+pub async fn delete_server_migration<S: AsRef<str>>(
+    session: &Session,
+    id: S,
+    migration_id: u64,
+) -> Result<()> {
+    ensure_api_version(
+        session,
+        API_VERSION_ABORT_MIGRATION,
+        "Aborting a live migration",
+    )
+    .await?;
+    debug!(
+        "Aborting migration {} of server {}",
+        migration_id,
+        id.as_ref()
+    );
+    let migration_id = migration_id.to_string();
+    let _ = session
+        .delete(
+            COMPUTE,
+            &["servers", id.as_ref(), "migrations", &migration_id],
+        )
+        .api_version(API_VERSION_ABORT_MIGRATION)
+        .send()
+        .await?;
+    debug!(
+        "Successfully requested abort of migration {} of server {}",
+        migration_id,
+        id.as_ref()
+    );
+    Ok(())
+}
+
 /// Delete a server.
 pub async fn delete_server<S: AsRef<str>>(session: &Session, id: S) -> Result<()> {
     trace!("Deleting server {}", id.as_ref());
@@ -102,6 +267,54 @@ pub async fn delete_server<S: AsRef<str>>(session: &Session, id: S) -> Result<()
     Ok(())
 }
 
+/// Delete a metadata item of a server.
+pub async fn delete_server_metadata_item<S1, S2>(session: &Session, id: S1, key: S2) -> Result<()>
+where
+    S1: AsRef<str>,
+    S2: AsRef<str>,
+{
+    debug!(
+        "Deleting metadata key {} of server {}",
+        key.as_ref(),
+        id.as_ref()
+    );
+    if let Err(err) = session
+        .delete(COMPUTE, &["servers", id.as_ref(), "metadata", key.as_ref()])
+        .send()
+        .await
+    {
+        return Err(metadata_item_error(session, err, id.as_ref(), key.as_ref()).await);
+    }
+    debug!(
+        "Metadata key {} of server {} was deleted",
+        key.as_ref(),
+        id.as_ref()
+    );
+    Ok(())
+}
+
+/// Ensure that the compute API supports the given version.
+///
+/// Fails with `IncompatibleApiVersion` if it does not.
+pub async fn ensure_api_version<S: AsRef<str>>(
+    session: &Session,
+    version: ApiVersion,
+    feature: S,
+) -> Result<()> {
+    if session.supports_api_version(COMPUTE, version).await? {
+        Ok(())
+    } else {
+        Err(Error::new(
+            ErrorKind::IncompatibleApiVersion,
+            format!(
+                "{} requires compute API version {} or newer",
+                feature.as_ref(),
+                version
+            ),
+        ))
+    }
+}
+
 /// Get a flavor by its ID.
 pub async fn get_extra_specs_by_flavor_id<S: AsRef<str>>(
     session: &Session,
@@ -154,6 +367,69 @@ pub async fn get_flavor_by_name<S: AsRef<str>>(session: &Session, name: S) -> Re
     get_flavor_by_id(session, item.id).await
 }
 
+/// Get aggregated hypervisor statistics.
+pub async fn get_hypervisor_statistics(session: &Session) -> Result<HypervisorStatistics> {
+    trace!("Fetching hypervisor statistics");
+    // Removed in API version 2.88, never request a newer version.
+    let root: HypervisorStatisticsRoot = session
+        .get_json(COMPUTE, &["os-hypervisors", "statistics"])
+        .await?;
+    trace!("Received {:?}", root.hypervisor_statistics);
+    Ok(root.hypervisor_statistics)
+}
+
+/// Get usage of a project within the given period.
+pub async fn get_tenant_usage<S: AsRef<str>>(
+    session: &Session,
+    project_id: S,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+) -> Result<TenantUsage> {
+    trace!(
+        "Fetching usage of project {} from {} to {}",
+        project_id.as_ref(),
+        start,
+        end
+    );
+    let mut root: TenantUsageRoot = session
+        .get(COMPUTE, &["os-simple-tenant-usage", project_id.as_ref()])
+        .query(&usage_query(start, end))
+        .fetch()
+        .await?;
+    // An empty object is returned for projects without usage.
+    if root.tenant_usage.tenant_id.is_empty() {
+        root.tenant_usage.tenant_id = project_id.as_ref().into();
+    }
+    trace!("Received {:?}", root.tenant_usage);
+    Ok(root.tenant_usage)
+}
+
+/// List usage of all projects within the given period.
+pub async fn list_tenant_usages(
+    session: &Session,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+) -> Result<Vec<TenantUsage>> {
+    trace!("Listing usage of all projects from {} to {}", start, end);
+    let mut query = usage_query(start, end);
+    query.push("detailed", 1);
+    let root: TenantUsagesRoot = session
+        .get(COMPUTE, &["os-simple-tenant-usage"])
+        .query(&query)
+        .fetch()
+        .await?;
+    trace!("Received usage: {:?}", root.tenant_usages);
+    Ok(root.tenant_usages)
+}
+
+fn usage_query(start: DateTime<Utc>, end: DateTime<Utc>) -> utils::Query {
+    const FORMAT: &str = "%Y-%m-%dT%H:%M:%S";
+    let mut query = utils::Query::new();
+    query.push("start", start.format(FORMAT));
+    query.push("end", end.format(FORMAT));
+    query
+}
+
 /// Get a key pair by its name.
 pub async fn get_keypair<S: AsRef<str>>(session: &Session, name: S) -> Result<KeyPair> {
     trace!("Get compute key pair by name {}", name.as_ref());
@@ -169,6 +445,154 @@ pub async fn get_keypair<S: AsRef<str>>(session: &Session, name: S) -> Result<Ke
     Ok(root.keypair)
 }
 
+/// Get a server group by its ID.
+pub async fn get_server_group<S: AsRef<str>>(session: &Session, id: S) -> Result<ServerGroup> {
+    trace!("Get compute server group {}", id.as_ref());
+    let maybe_version = session
+        .pick_api_version(COMPUTE, Some(API_VERSION_SERVER_GROUP_POLICY))
+        .await?;
+    let mut builder = session.get(COMPUTE, &["os-server-groups", id.as_ref()]);
+    if let Some(version) = maybe_version {
+        builder.set_api_version(version);
+    }
+    let root: ServerGroupRoot = builder.fetch().await?;
+    trace!("Received {:?}", root.server_group);
+    Ok(root.server_group)
+}
+
+/// Get a quota class.
+pub async fn get_quota_class<S: AsRef<str>>(session: &Session, name: S) -> Result<QuotaSet> {
+    trace!("Get quota class {}", name.as_ref());
+    let root: QuotaClassSetRoot = session
+        .get_json(COMPUTE, &["os-quota-class-sets", name.as_ref()])
+        .await?;
+    trace!("Received {:?}", root.quota_class_set);
+    Ok(root.quota_class_set)
+}
+
+/// Get the console output (log) of a server.
+///
+/// A `length` of zero always results in an empty output without contacting the server, since
+/// the Compute service versions disagree on its meaning.
+pub async fn get_console_output<S: AsRef<str>>(
+    session: &Session,
+    id: S,
+    length: Option<u32>,
+) -> Result<String> {
+    if length == Some(0) {
+        return Ok(String::new());
+    }
+    trace!(
+        "Requesting console output of server {} with length {:?}",
+        id.as_ref(),
+        length
+    );
+    let body = server_action_body("os-getConsoleOutput", ConsoleOutputRequest { length });
+    let root: ConsoleOutputRoot = session
+        .post(COMPUTE, &["servers", id.as_ref(), "action"])
+        .json(&body)
+        .fetch()
+        .await?;
+    let output = root.output.unwrap_or_default();
+    debug!(
+        "Received {} bytes of console output for server {}",
+        output.len(),
+        id.as_ref()
+    );
+    Ok(output)
+}
+
+/// Get a remote console of a server.
+///
+/// Uses the remote consoles API if available, falls back to the older server actions.
+pub async fn get_remote_console<S: AsRef<str>>(
+    session: &Session,
+    id: S,
+    protocol: ConsoleProtocol,
+    console_type: ConsoleType,
+) -> Result<RemoteConsole> {
+    trace!(
+        "Requesting {:?} console of type {:?} for server {}",
+        protocol,
+        console_type,
+        id.as_ref()
+    );
+    let console = if session
+        .supports_api_version(COMPUTE, API_VERSION_REMOTE_CONSOLES)
+        .await?
+    {
+        let body = RemoteConsoleCreateRoot {
+            remote_console: RemoteConsoleCreate {
+                protocol,
+                console_type,
+            },
+        };
+        let root: RemoteConsoleRoot = session
+            .post(COMPUTE, &["servers", id.as_ref(), "remote-consoles"])
+            .api_version(API_VERSION_REMOTE_CONSOLES)
+            .json(&body)
+            .fetch()
+            .await?;
+        root.remote_console
+    } else {
+        let body = server_action_body(
+            legacy_console_action(protocol),
+            ConsoleRequest { console_type },
+        );
+        let root: ConsoleRoot = session
+            .post(COMPUTE, &["servers", id.as_ref(), "action"])
+            .json(&body)
+            .fetch()
+            .await?;
+        RemoteConsole {
+            protocol,
+            console_type: root.console.console_type,
+            url: root.console.url,
+            received_at: Utc::now(),
+        }
+    };
+    debug!("Received {:?} console for server {}", protocol, id.as_ref());
+    Ok(console)
+}
+
+fn legacy_console_action(protocol: ConsoleProtocol) -> &'static str {
+    match protocol {
+        ConsoleProtocol::Rdp => "os-getRDPConsole",
+        ConsoleProtocol::Serial => "os-getSerialConsole",
+        ConsoleProtocol::Spice => "os-getSPICEConsole",
+        ConsoleProtocol::Vnc => "os-getVNCConsole",
+    }
+}
+
+/// Convert an error of a metadata item call.
+///
+/// Nova returns 404 both for a missing server and for a missing key, so on 404 the server
+/// is fetched to tell them apart.
+async fn metadata_item_error(session: &Session, err: Error, id: &str, key: &str) -> Error {
+    if err.kind() != ErrorKind::ResourceNotFound {
+        return err;
+    }
+    let server_exists = match get_server_by_id(session, id).await {
+        Ok(..) => true,
+        Err(check_err) => {
+            debug!("Cannot check whether server {} exists: {}", id, check_err);
+            false
+        }
+    };
+    metadata_key_error(err, server_exists, id, key)
+}
+
+fn metadata_key_error(err: Error, server_exists: bool, id: &str, key: &str) -> Error {
+    if server_exists {
+        Error::new(
+            ErrorKind::ResourceNotFound,
+            format!("Metadata key {} not found on server {}", key, id),
+        )
+    } else {
+        err
+    }
+}
+
 /// Get a server.
 pub async fn get_server<S: AsRef<str>>(session: &Session, id_or_name: S) -> Result<Server> {
     let s = id_or_name.as_ref();
@@ -184,16 +608,38 @@ pub async fn get_server<S: AsRef<str>>(session: &Session, id_or_name: S) -> Resu
 /// Get a server by its ID.
 pub async fn get_server_by_id<S: AsRef<str>>(session: &Session, id: S) -> Result<Server> {
     trace!("Get compute server with ID {}", id.as_ref());
+    let root: ServerRoot = server_by_id_request(session, id).await?.fetch().await?;
+    trace!("Received {:?}", root.server);
+    Ok(root.server)
+}
+
+/// Get a server by its ID together with the raw response body.
+pub async fn get_server_by_id_raw<S: AsRef<str>>(
+    session: &Session,
+    id: S,
+) -> Result<(Server, serde_json::Value)> {
+    trace!("Get compute server with ID {} (raw)", id.as_ref());
+    let body: serde_json::Value = server_by_id_request(session, id).await?.fetch().await?;
+    trace!("Received {}", body);
+    let root: ServerRoot = utils::from_raw_json(&body)?;
+    Ok((root.server, body))
+}
+
+async fn server_by_id_request<S: AsRef<str>>(
+    session: &Session,
+    id: S,
+) -> Result<ServiceRequestBuilder<ComputeService>> {
     let maybe_version = session
-        .pick_api_version(COMPUTE, Some(API_VERSION_SERVER_DESCRIPTION))
+        .pick_api_version(
+            COMPUTE,
+            vec![API_VERSION_SERVER_DESCRIPTION, API_VERSION_SERVER_TAGS],
+        )
         .await?;
     let mut builder = session.get(COMPUTE, &["servers", id.as_ref()]);
     if let Some(version) = maybe_version {
         builder.set_api_version(version);
     }
-    let root: ServerRoot = builder.fetch().await?;
-    trace!("Received {:?}", root.server);
-    Ok(root.server)
+    Ok(builder)
 }
 
 /// Get a server by its name.
@@ -214,6 +660,65 @@ pub async fn get_server_by_name<S: AsRef<str>>(session: &Session, name: S) -> Re
     get_server_by_id(session, item.id).await
 }
 
+/// Get metadata of a server.
+pub async fn get_server_metadata<S: AsRef<str>>(
+    session: &Session,
+    id: S,
+) -> Result<HashMap<String, String>> {
+    trace!("Get metadata of server {}", id.as_ref());
+    let root: MetadataRoot = session
+        .get_json(COMPUTE, &["servers", id.as_ref(), "metadata"])
+        .await?;
+    trace!("Received {:?}", root.metadata);
+    Ok(root.metadata)
+}
+
+/// Get the attachment of a volume to a server (if any).
+///
+/// The attachment is identified by the ID of the volume.
+pub async fn get_volume_attachment<S1, S2>(
+    session: &Session,
+    id: S1,
+    volume_id: S2,
+) -> Result<Option<VolumeAttachment>>
+where
+    S1: AsRef<str>,
+    S2: AsRef<str>,
+{
+    trace!(
+        "Get attachment of volume {} to server {}",
+        volume_id.as_ref(),
+        id.as_ref()
+    );
+    let result: Result<VolumeAttachmentRoot> = session
+        .get_json(
+            COMPUTE,
+            &[
+                "servers",
+                id.as_ref(),
+                "os-volume_attachments",
+                volume_id.as_ref(),
+            ],
+        )
+        .await;
+    match result {
+        Ok(root) => {
+            trace!("Received {:?}", root.volume_attachment);
+            Ok(Some(root.volume_attachment))
+        }
+        Err(err) if err.kind() == ErrorKind::ResourceNotFound => Ok(None),
+        Err(err) => Err(err),
+    }
+}
+
+/// Get details of the compute API version in use.
+pub async fn get_version_details(session: &Session) -> Result<VersionDetails> {
+    trace!("Fetching compute API version details");
+    let root: VersionRoot = session.get_json(COMPUTE, NO_PATH).await?;
+    trace!("Received {:?}", root.version);
+    Ok(root.version)
+}
+
 /// List flavors.
 pub async fn list_flavors<Q: Serialize + Sync + Debug>(
     session: &Session,
@@ -273,17 +778,72 @@ pub async fn list_keypairs<Q: Serialize + Sync + Debug>(
     Ok(result)
 }
 
+/// List migrations across the deployment.
+pub async fn list_migrations<Q: Serialize + Sync + Debug>(
+    session: &Session,
+    query: &Q,
+) -> Result<Vec<Migration>> {
+    trace!("Listing migrations with {:?}", query);
+    let maybe_version = session
+        .pick_api_version(COMPUTE, Some(API_VERSION_SERVER_MIGRATIONS))
+        .await?;
+    let mut builder = session.get(COMPUTE, &["os-migrations"]).query(query);
+    if let Some(version) = maybe_version {
+        builder.set_api_version(version);
+    }
+    let root: MigrationsRoot = builder.fetch().await?;
+    trace!("Received migrations: {:?}", root.migrations);
+    Ok(root.migrations)
+}
+
+/// List server groups.
+pub async fn list_server_groups(session: &Session) -> Result<Vec<ServerGroup>> {
+    trace!("Listing compute server groups");
+    let maybe_version = session
+        .pick_api_version(COMPUTE, Some(API_VERSION_SERVER_GROUP_POLICY))
+        .await?;
+    let mut builder = session.get(COMPUTE, &["os-server-groups"]);
+    if let Some(version) = maybe_version {
+        builder.set_api_version(version);
+    }
+    let root: ServerGroupsRoot = builder.fetch().await?;
+    trace!("Received server groups: {:?}", root.server_groups);
+    Ok(root.server_groups)
+}
+
+/// List in-progress migrations of a server.
+pub async fn list_server_migrations<S: AsRef<str>>(
+    session: &Session,
+    id: S,
+) -> Result<Vec<Migration>> {
+    ensure_api_version(
+        session,
+        API_VERSION_SERVER_MIGRATIONS,
+        "Listing server migrations",
+    )
+    .await?;
+    trace!("Listing migrations of server {}", id.as_ref());
+    let root: MigrationsRoot = session
+        .get(COMPUTE, &["servers", id.as_ref(), "migrations"])
+        .api_version(API_VERSION_SERVER_MIGRATIONS)
+        .fetch()
+        .await?;
+    trace!("Received server migrations: {:?}", root.migrations);
+    Ok(root.migrations)
+}
+
 /// List servers.
 pub async fn list_servers<Q: Serialize + Sync + Debug>(
     session: &Session,
     query: &Q,
+    api_version: Option<ApiVersion>,
 ) -> Result<Vec<IdAndName>> {
     trace!("Listing compute servers with {:?}", query);
-    let root: ServersRoot = session
-        .get(COMPUTE, &["servers"])
-        .query(query)
-        .fetch()
-        .await?;
+    let mut builder = session.get(COMPUTE, &["servers"]).query(query);
+    if let Some(version) = api_version {
+        builder.set_api_version(version);
+    }
+    let root: ServersRoot = builder.fetch().await?;
     trace!("Received servers: {:?}", root.servers);
     Ok(root.servers)
 }
@@ -292,18 +852,46 @@ pub async fn list_servers<Q: Serialize + Sync + Debug>(
 pub async fn list_servers_detail<Q: Serialize + Sync + Debug>(
     session: &Session,
     query: &Q,
+    api_version: Option<ApiVersion>,
 ) -> Result<Vec<Server>> {
     trace!("Listing compute servers with {:?}", query);
-    let maybe_version = session
-        .pick_api_version(COMPUTE, Some(API_VERSION_SERVER_DESCRIPTION))
+    let root: ServersDetailRoot = servers_detail_request(session, query, api_version)
+        .await?
+        .fetch()
+        .await?;
+    trace!("Received servers: {:?}", root.servers);
+    Ok(root.servers)
+}
+
+/// List servers with details together with the raw response body.
+pub async fn list_servers_detail_raw<Q: Serialize + Sync + Debug>(
+    session: &Session,
+    query: &Q,
+    api_version: Option<ApiVersion>,
+) -> Result<(Vec<Server>, serde_json::Value)> {
+    trace!("Listing compute servers with {:?} (raw)", query);
+    let body: serde_json::Value = servers_detail_request(session, query, api_version)
+        .await?
+        .fetch()
         .await?;
+    trace!("Received servers: {}", body);
+    let root: ServersDetailRoot = utils::from_raw_json(&body)?;
+    Ok((root.servers, body))
+}
+
+async fn servers_detail_request<Q: Serialize + Sync + Debug>(
+    session: &Session,
+    query: &Q,
+    api_version: Option<ApiVersion>,
+) -> Result<ServiceRequestBuilder<ComputeService>> {
+    let mut versions = vec![API_VERSION_SERVER_DESCRIPTION, API_VERSION_SERVER_TAGS];
+    versions.extend(api_version);
+    let maybe_version = session.pick_api_version(COMPUTE, versions).await?;
     let mut builder = session.get(COMPUTE, &["servers", "detail"]).query(query);
     if let Some(version) = maybe_version {
         builder.set_api_version(version);
     }
-    let root: ServersDetailRoot = builder.fetch().await?;
-    trace!("Received servers: {:?}", root.servers);
-    Ok(root.servers)
+    Ok(builder)
 }
 
 /// Run an action while providing some arguments.
@@ -324,19 +912,117 @@ where
         id.as_ref(),
         args
     );
+    let body = server_action_body(action.as_ref(), args);
+    send_server_action(session, id.as_ref(), action.as_ref(), body).await
+}
+
+fn server_action_body<Q>(action: &str, args: Q) -> HashMap<&str, Q> {
     let mut body = HashMap::new();
-    let _ = body.insert(action.as_ref(), args);
-    let _ = session
+    let _ = body.insert(action, args);
+    body
+}
+
+async fn send_server_action<B: Serialize + Send>(
+    session: &Session,
+    id: &str,
+    action: &str,
+    body: B,
+) -> Result<()> {
+    let request = session
+        .post(COMPUTE, &["servers", id, "action"])
+        .json(&body);
+    let _ = request.send().await?;
+    debug!("Successfully ran {} on server {}", action, id);
+    Ok(())
+}
+
+/// Reboot a server.
+pub async fn reboot_server<S: AsRef<str>>(
+    session: &Session,
+    id: S,
+    reboot_type: RebootType,
+) -> Result<()> {
+    trace!("Rebooting server {} ({:?})", id.as_ref(), reboot_type);
+    send_server_action(session, id.as_ref(), "reboot", reboot_body(reboot_type)).await
+}
+
+fn reboot_body(reboot_type: RebootType) -> serde_json::Value {
+    serde_json::json!({"reboot": {"type": reboot_type}})
+}
+
+/// Resize a server to a new flavor.
+pub async fn resize_server<S1, S2>(session: &Session, id: S1, flavor_id: S2) -> Result<()>
+where
+    S1: AsRef<str>,
+    S2: AsRef<str>,
+{
+    trace!(
+        "Resizing server {} to flavor {}",
+        id.as_ref(),
+        flavor_id.as_ref()
+    );
+    send_server_action(
+        session,
+        id.as_ref(),
+        "resize",
+        resize_body(flavor_id.as_ref()),
+    )
+    .await
+}
+
+fn resize_body(flavor_id: &str) -> serde_json::Value {
+    serde_json::json!({"resize": {"flavorRef": flavor_id}})
+}
+
+/// Reset the state of a server (`active` or `error`).
+pub async fn reset_server_state<S: AsRef<str>>(
+    session: &Session,
+    id: S,
+    state: &str,
+) -> Result<()> {
+    trace!("Resetting state of server {} to {}", id.as_ref(), state);
+    send_server_action(
+        session,
+        id.as_ref(),
+        "os-resetState",
+        reset_state_body(state),
+    )
+    .await
+}
+
+fn reset_state_body(state: &str) -> serde_json::Value {
+    serde_json::json!({"os-resetState": {"state": state}})
+}
+
+/// Run an arbitrary action on the server, returning the response body.
+///
+/// Returns `Null` if the response has no body.
+pub async fn server_raw_action<S: AsRef<str>>(
+    session: &Session,
+    id: S,
+    body: &serde_json::Value,
+) -> Result<serde_json::Value> {
+    trace!("Running raw action {} on server {}", body, id.as_ref());
+    let response = session
         .post(COMPUTE, &["servers", id.as_ref(), "action"])
-        .json(&body)
+        .json(body)
         .send()
         .await?;
-    debug!(
-        "Successfully ran {} on server {}",
-        action.as_ref(),
-        id.as_ref()
-    );
-    Ok(())
+    let result = parse_action_response(&response.bytes().await?)?;
+    debug!("Successfully ran raw action on server {}", id.as_ref());
+    Ok(result)
+}
+
+fn parse_action_response(body: &[u8]) -> Result<serde_json::Value> {
+    if body.is_empty() {
+        return Ok(serde_json::Value::Null);
+    }
+    serde_json::from_slice(body).map_err(|err| {
+        Error::new(
+            ErrorKind::InvalidResponse,
+            format!("Cannot parse server action response: {}", err),
+        )
+    })
 }
 
 /// Run an action on the server.
@@ -348,6 +1034,56 @@ where
     server_action_with_args(session, id, action, serde_json::Value::Null).await
 }
 
+/// Set one metadata item of a server.
+pub async fn set_server_metadata_item<S1, S2, S3>(
+    session: &Session,
+    id: S1,
+    key: S2,
+    value: S3,
+) -> Result<()>
+where
+    S1: AsRef<str>,
+    S2: Into<String>,
+    S3: Into<String>,
+{
+    let key = key.into();
+    let mut meta = HashMap::new();
+    let _ = meta.insert(key.clone(), value.into());
+    debug!("Setting metadata {:?} on server {}", meta, id.as_ref());
+    let body = MetadataItemRoot { meta };
+    if let Err(err) = session
+        .put(COMPUTE, &["servers", id.as_ref(), "metadata", &key])
+        .json(&body)
+        .send()
+        .await
+    {
+        return Err(metadata_item_error(session, err, id.as_ref(), &key).await);
+    }
+    debug!("Set metadata key {} on server {}", key, id.as_ref());
+    Ok(())
+}
+
+/// Replace all tags of a server.
+///
+/// Returns the resulting tags.
+pub async fn set_server_tags<S: AsRef<str>>(
+    session: &Session,
+    id: S,
+    tags: Vec<String>,
+) -> Result<Vec<String>> {
+    debug!("Setting tags of server {} to {:?}", id.as_ref(), tags);
+    ensure_api_version(session, API_VERSION_SERVER_TAGS, "Server tags").await?;
+    let body = TagsRoot { tags };
+    let root: TagsRoot = session
+        .put(COMPUTE, &["servers", id.as_ref(), "tags"])
+        .api_version(API_VERSION_SERVER_TAGS)
+        .json(&body)
+        .fetch()
+        .await?;
+    trace!("Server {} now has tags {:?}", id.as_ref(), root.tags);
+    Ok(root.tags)
+}
+
 /// Whether key pair pagination is supported.
 #[inline]
 pub async fn supports_keypair_pagination(session: &Session) -> Result<bool> {
@@ -355,3 +1091,324 @@ pub async fn supports_keypair_pagination(session: &Session) -> Result<bool> {
         .supports_api_version(COMPUTE, API_VERSION_KEYPAIR_PAGINATION)
         .await
 }
+
+/// Update a quota class.
+pub async fn update_quota_class<S: AsRef<str>>(
+    session: &Session,
+    name: S,
+    update: QuotaSetUpdate,
+) -> Result<QuotaSet> {
+    debug!("Updating quota class {} with {:?}", name.as_ref(), update);
+    let body = QuotaClassSetUpdateRoot {
+        quota_class_set: update,
+    };
+    let root: QuotaClassSetRoot = session
+        .put(COMPUTE, &["os-quota-class-sets", name.as_ref()])
+        .json(&body)
+        .fetch()
+        .await?;
+    debug!(
+        "Quota class {} is now {:?}",
+        name.as_ref(),
+        root.quota_class_set
+    );
+    Ok(root.quota_class_set)
+}
+
+/// Update a server.
+pub async fn update_server<S: AsRef<str>>(
+    session: &Session,
+    id: S,
+    update: ServerUpdate,
+) -> Result<()> {
+    debug!("Updating server {} with {:?}", id.as_ref(), update);
+    let body = ServerUpdateRoot { server: update };
+    let _ = session
+        .put(COMPUTE, &["servers", id.as_ref()])
+        .json(&body)
+        .send()
+        .await?;
+    debug!("Successfully updated server {}", id.as_ref());
+    Ok(())
+}
+
+/// Merge the given metadata into the metadata of a server.
+///
+/// Returns the resulting metadata.
+pub async fn update_server_metadata<S: AsRef<str>>(
+    session: &Session,
+    id: S,
+    metadata: HashMap<String, String>,
+) -> Result<HashMap<String, String>> {
+    debug!(
+        "Updating metadata of server {} with {:?}",
+        id.as_ref(),
+        metadata
+    );
+    let body = MetadataRoot { metadata };
+    let root: MetadataRoot = session
+        .post(COMPUTE, &["servers", id.as_ref(), "metadata"])
+        .json(&body)
+        .fetch()
+        .await?;
+    trace!(
+        "Server {} now has metadata {:?}",
+        id.as_ref(),
+        root.metadata
+    );
+    Ok(root.metadata)
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::{TimeZone, Utc};
+    use osauth::NoAuth;
+
+    use super::super::super::session::Session;
+    use super::super::super::utils;
+    use super::super::super::{Error, ErrorKind};
+    use super::super::protocol::{
+        ConsoleOutputRequest, ConsoleProtocol, ConsoleRequest, ConsoleType, RebootType,
+        ServerGroupCreate, ServersDetailRoot,
+    };
+    use super::{
+        get_console_output, legacy_console_action, metadata_item_error, metadata_key_error,
+        parse_action_response, reboot_body, reset_state_body, resize_body, server_action_body,
+        server_group_create_body, usage_query,
+    };
+
+    #[test]
+    fn test_metadata_key_error() {
+        // The server exists, so the key is missing, whatever the message says.
+        let err = metadata_key_error(
+            Error::new(ErrorKind::ResourceNotFound, "Not found"),
+            true,
+            "server-id",
+            "role",
+        );
+        assert_eq!(err.kind(), ErrorKind::ResourceNotFound);
+        assert!(err.to_string().contains("Metadata key role not found"));
+
+        // A missing server is reported as is.
+        let err = metadata_key_error(
+            Error::new(
+                ErrorKind::ResourceNotFound,
+                "Instance server-id could not be found.",
+            ),
+            false,
+            "server-id",
+            "role",
+        );
+        assert_eq!(err.kind(), ErrorKind::ResourceNotFound);
+        assert!(err
+            .to_string()
+            .contains("Instance server-id could not be found"));
+    }
+
+    #[tokio::test]
+    async fn test_metadata_item_error() {
+        let auth = NoAuth::new("http://127.0.0.1:1/compute").unwrap();
+        let session = Session::new(auth).await.unwrap();
+
+        let err = metadata_item_error(
+            &session,
+            Error::new(ErrorKind::AccessDenied, "Forbidden"),
+            "server-id",
+            "role",
+        )
+        .await;
+        assert_eq!(err.kind(), ErrorKind::AccessDenied);
+
+        // The server check fails, so the original error is kept.
+        let err = metadata_item_error(
+            &session,
+            Error::new(ErrorKind::ResourceNotFound, "Not found"),
+            "server-id",
+            "role",
+        )
+        .await;
+        assert_eq!(err.kind(), ErrorKind::ResourceNotFound);
+        assert!(err.to_string().contains("Not found"));
+        assert!(!err.to_string().contains("Metadata key"));
+    }
+
+    #[test]
+    fn test_console_output_body() {
+        let body = server_action_body("os-getConsoleOutput", ConsoleOutputRequest { length: None });
+        assert_eq!(
+            serde_json::to_value(&body).unwrap(),
+            serde_json::json!({"os-getConsoleOutput": {}})
+        );
+        let body = server_action_body(
+            "os-getConsoleOutput",
+            ConsoleOutputRequest { length: Some(50) },
+        );
+        assert_eq!(
+            serde_json::to_value(&body).unwrap(),
+            serde_json::json!({"os-getConsoleOutput": {"length": 50}})
+        );
+    }
+
+    #[tokio::test]
+    async fn test_console_output_zero_length() {
+        // Nothing listens on this port, the request must not be made.
+        let auth = NoAuth::new("http://127.0.0.1:1/compute").unwrap();
+        let session = Session::new(auth).await.unwrap();
+        let output = get_console_output(&session, "server-id", Some(0))
+            .await
+            .unwrap();
+        assert_eq!(output, "");
+    }
+
+    #[test]
+    fn test_server_simple_action_body() {
+        for (action, expected) in &[
+            ("restore", r#"{"restore":null}"#),
+            ("forceDelete", r#"{"forceDelete":null}"#),
+            ("os-start", r#"{"os-start":null}"#),
+            ("os-stop", r#"{"os-stop":null}"#),
+            ("pause", r#"{"pause":null}"#),
+            ("unpause", r#"{"unpause":null}"#),
+            ("suspend", r#"{"suspend":null}"#),
+            ("resume", r#"{"resume":null}"#),
+            ("confirmResize", r#"{"confirmResize":null}"#),
+            ("revertResize", r#"{"revertResize":null}"#),
+        ] {
+            let body = server_action_body(action, serde_json::Value::Null);
+            assert_eq!(serde_json::to_string(&body).unwrap(), *expected);
+        }
+    }
+
+    #[test]
+    fn test_server_group_create_body() {
+        let request = ServerGroupCreate::new("group".into(), "anti-affinity".into());
+        assert_eq!(
+            serde_json::to_value(server_group_create_body(request.clone(), true)).unwrap(),
+            serde_json::json!({
+                "server_group": {"name": "group", "policy": "anti-affinity"}
+            })
+        );
+        assert_eq!(
+            serde_json::to_value(server_group_create_body(request, false)).unwrap(),
+            serde_json::json!({
+                "server_group": {"name": "group", "policies": ["anti-affinity"]}
+            })
+        );
+    }
+
+    #[test]
+    fn test_reboot_action_body() {
+        for (reboot_type, expected) in &[(RebootType::Hard, "HARD"), (RebootType::Soft, "SOFT")] {
+            assert_eq!(
+                reboot_body(*reboot_type),
+                serde_json::json!({"reboot": {"type": expected}})
+            );
+        }
+    }
+
+    #[test]
+    fn test_reset_state_action_body() {
+        assert_eq!(
+            reset_state_body("active"),
+            serde_json::json!({"os-resetState": {"state": "active"}})
+        );
+    }
+
+    #[test]
+    fn test_resize_action_body() {
+        assert_eq!(
+            resize_body("2"),
+            serde_json::json!({"resize": {"flavorRef": "2"}})
+        );
+    }
+
+    #[test]
+    fn test_parse_action_response() {
+        assert_eq!(parse_action_response(b"").unwrap(), serde_json::Value::Null);
+        assert_eq!(
+            parse_action_response(br#"{"output": "boot log"}"#).unwrap(),
+            serde_json::json!({"output": "boot log"})
+        );
+        assert_eq!(
+            parse_action_response(b"<html>").unwrap_err().kind(),
+            ErrorKind::InvalidResponse
+        );
+    }
+
+    #[test]
+    fn test_legacy_console_action_body() {
+        let body = server_action_body(
+            legacy_console_action(ConsoleProtocol::Spice),
+            ConsoleRequest {
+                console_type: ConsoleType::SpiceHtml5,
+            },
+        );
+        assert_eq!(
+            serde_json::to_value(&body).unwrap(),
+            serde_json::json!({"os-getSPICEConsole": {"type": "spice-html5"}})
+        );
+    }
+
+    #[test]
+    fn test_serial_console_action_body() {
+        let body = server_action_body(
+            legacy_console_action(ConsoleProtocol::Serial),
+            ConsoleRequest {
+                console_type: ConsoleType::Serial,
+            },
+        );
+        assert_eq!(
+            serde_json::to_value(&body).unwrap(),
+            serde_json::json!({"os-getSerialConsole": {"type": "serial"}})
+        );
+    }
+
+    #[test]
+    fn test_servers_detail_raw() {
+        let body = serde_json::json!({
+            "servers": [{
+                "OS-EXT-AZ:availability_zone": "nova",
+                "config_drive": "",
+                "created": "2020-01-01T00:00:00Z",
+                "flavor": {"id": "1", "links": []},
+                "id": "abcd",
+                "name": "test",
+                "status": "ACTIVE",
+                "tenant_id": "demo",
+                "updated": "2020-01-01T00:00:00Z",
+                "user_id": "demo",
+                "x-vendor-field": 42
+            }]
+        });
+        let root: ServersDetailRoot = utils::from_raw_json(&body).unwrap();
+        assert_eq!(root.servers.len(), 1);
+        assert_eq!(root.servers[0].id, "abcd");
+        // Fields unknown to the crate are still visible in the raw body
+        assert_eq!(body["servers"][0]["x-vendor-field"], 42);
+
+        let mut broken = body;
+        broken["servers"][0]["status"] = "NOT-A-STATUS".into();
+        let err = utils::from_raw_json::<ServersDetailRoot>(&broken).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidResponse);
+        assert!(err.to_string().contains("NOT-A-STATUS"));
+
+        broken["servers"][0]["description"] = "x".repeat(10_000).into();
+        let err = utils::from_raw_json::<ServersDetailRoot>(&broken).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidResponse);
+        assert!(err.to_string().contains("..."));
+        assert!(err.to_string().len() < 2000);
+    }
+
+    #[test]
+    fn test_usage_query() {
+        let start = Utc.with_ymd_and_hms(2020, 1, 1, 0, 0, 0).unwrap();
+        let end = Utc.with_ymd_and_hms(2020, 2, 1, 12, 30, 0).unwrap();
+        assert_eq!(
+            usage_query(start, end).0,
+            vec![
+                ("start".to_string(), "2020-01-01T00:00:00".to_string()),
+                ("end".to_string(), "2020-02-01T12:30:00".to_string()),
+            ]
+        );
+    }
+}
@@ -19,8 +19,9 @@ use std::collections::HashMap;
 use async_trait::async_trait;
 use futures::stream::{Stream, TryStreamExt};
 use osauth::common::IdAndName;
+use reqwest::Url;
 
-use super::super::common::{FlavorRef, Refresh, ResourceIterator, ResourceQuery};
+use super::super::common::{FlavorRef, Link, Refresh, ResourceIterator, ResourceQuery};
 use super::super::session::Session;
 use super::super::utils::Query;
 use super::super::Result;
@@ -76,13 +77,30 @@ impl Flavor {
         Flavor::new(session, inner).await
     }
 
+    /// Bookmark URL of the flavor (if provided by the server).
+    ///
+    /// Unlike the `self` link, the bookmark link does not contain the API version.
+    pub fn bookmark_url(&self) -> Option<&Url> {
+        self.inner
+            .links
+            .iter()
+            .find(|link| link.rel == "bookmark")
+            .map(|link| &link.href)
+    }
+
     /// Get ephemeral disk size in GiB.
     ///
     /// Returns `0` when ephemeral disk was not requested.
-    pub fn emphemeral_size(&self) -> u64 {
+    pub fn ephemeral_size(&self) -> u64 {
         self.inner.ephemeral
     }
 
+    /// Get ephemeral disk size in GiB.
+    #[deprecated(since = "0.5.1", note = "use ephemeral_size")]
+    pub fn emphemeral_size(&self) -> u64 {
+        self.ephemeral_size()
+    }
+
     /// Extra specs of the flavor.
     pub fn extra_specs(&self) -> &HashMap<String, String> {
         &self.extra_specs
@@ -98,6 +116,11 @@ impl Flavor {
         self.inner.is_public
     }
 
+    /// Links to the flavor.
+    pub fn links(&self) -> &[Link] {
+        &self.inner.links
+    }
+
     /// Get a reference to flavor name.
     pub fn name(&self) -> &String {
         &self.inner.name
@@ -0,0 +1,257 @@
+// Copyright 2026 Dmitry Tantsur <divius.inside@gmail.com>
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Migration monitoring via Compute API.
+
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+use futures::stream::{Stream, TryStreamExt};
+
+use super::super::common::{ResourceIterator, ResourceQuery};
+use super::super::session::Session;
+use super::super::utils::Query;
+use super::super::Result;
+use super::{api, protocol};
+
+/// A migration of a server.
+///
+/// Returned both by the deployment-wide list and by [Server::migrations]. Some fields are
+/// only set by one of them (see their documentation).
+///
+/// [Server::migrations]: super::Server::migrations
+#[derive(Clone, Debug)]
+pub struct Migration {
+    inner: protocol::Migration,
+}
+
+impl Migration {
+    pub(crate) fn new(inner: protocol::Migration) -> Migration {
+        Migration { inner }
+    }
+
+    transparent_property! {
+        #[doc = "Creation date and time (UTC)."]
+        created_at: NaiveDateTime
+    }
+
+    transparent_property! {
+        #[doc = "Destination compute service (if known)."]
+        dest_compute: ref Option<String>
+    }
+
+    transparent_property! {
+        #[doc = "Destination host (if known)."]
+        dest_host: ref Option<String>
+    }
+
+    transparent_property! {
+        #[doc = "Destination node (if known)."]
+        dest_node: ref Option<String>
+    }
+
+    transparent_property! {
+        #[doc = "Amount of disk data transferred so far in bytes (live migrations only)."]
+        disk_processed_bytes: Option<u64>
+    }
+
+    transparent_property! {
+        #[doc = "Amount of disk data left to transfer in bytes (live migrations only)."]
+        disk_remaining_bytes: Option<u64>
+    }
+
+    transparent_property! {
+        #[doc = "Total amount of disk data to transfer in bytes (live migrations only)."]
+        disk_total_bytes: Option<u64>
+    }
+
+    transparent_property! {
+        #[doc = "Migration ID."]
+        id: u64
+    }
+
+    transparent_property! {
+        #[doc = "Amount of memory transferred so far in bytes (live migrations only)."]
+        memory_processed_bytes: Option<u64>
+    }
+
+    transparent_property! {
+        #[doc = "Amount of memory left to transfer in bytes (live migrations only)."]
+        memory_remaining_bytes: Option<u64>
+    }
+
+    transparent_property! {
+        #[doc = "Total amount of memory to transfer in bytes (live migrations only)."]
+        memory_total_bytes: Option<u64>
+    }
+
+    transparent_property! {
+        #[doc = "Migration type, e.g. `live-migration` or `evacuation`."]
+        #[doc = ""]
+        #[doc = "Only set in the deployment-wide list with compute API version 2.23 or newer."]
+        migration_type: ref Option<String>
+    }
+
+    transparent_property! {
+        #[doc = "ID of the flavor after the migration (deployment-wide list only)."]
+        new_instance_type_id: Option<u64>
+    }
+
+    transparent_property! {
+        #[doc = "ID of the flavor before the migration (deployment-wide list only)."]
+        old_instance_type_id: Option<u64>
+    }
+
+    transparent_property! {
+        #[doc = "ID of the migrated server."]
+        server_id: ref String
+    }
+
+    transparent_property! {
+        #[doc = "Source compute service (if known)."]
+        source_compute: ref Option<String>
+    }
+
+    transparent_property! {
+        #[doc = "Source node (if known)."]
+        source_node: ref Option<String>
+    }
+
+    transparent_property! {
+        #[doc = "Migration status."]
+        status: ref String
+    }
+
+    transparent_property! {
+        #[doc = "Last update date and time (UTC, if available)."]
+        updated_at: Option<NaiveDateTime>
+    }
+}
+
+/// A query to the deployment-wide migration list.
+///
+/// Requires admin privileges.
+#[derive(Clone, Debug)]
+pub struct MigrationQuery {
+    session: Session,
+    query: Query,
+}
+
+impl MigrationQuery {
+    pub(crate) fn new(session: Session) -> MigrationQuery {
+        MigrationQuery {
+            session,
+            query: Query::new(),
+        }
+    }
+
+    query_filter! {
+        #[doc = "Filter by the host the migration involves."]
+        set_host, with_host -> host
+    }
+
+    query_filter! {
+        #[doc = "Filter by migration type (e.g. `live-migration` or `evacuation`)."]
+        set_migration_type, with_migration_type -> migration_type
+    }
+
+    query_filter! {
+        #[doc = "Filter by source compute service."]
+        set_source_compute, with_source_compute -> source_compute
+    }
+
+    query_filter! {
+        #[doc = "Filter by migration status."]
+        set_status, with_status -> status
+    }
+
+    /// Convert this query into a stream executing the request.
+    ///
+    /// Returns a `TryStream`, which is a stream with each `next`
+    /// call returning a `Result`.
+    ///
+    /// Note that no requests are done until you start iterating.
+    pub fn into_stream(self) -> impl Stream<Item = Result<Migration>> {
+        debug!("Fetching migrations with {:?}", self.query);
+        ResourceIterator::new(self).into_stream()
+    }
+
+    /// Execute this request and return all results.
+    ///
+    /// A convenience shortcut for `self.into_stream().try_collect().await`.
+    pub async fn all(self) -> Result<Vec<Migration>> {
+        self.into_stream().try_collect().await
+    }
+}
+
+#[async_trait]
+impl ResourceQuery for MigrationQuery {
+    type Item = Migration;
+
+    const DEFAULT_LIMIT: usize = 100;
+
+    async fn can_paginate(&self) -> Result<bool> {
+        // Pagination requires API version 2.59 and UUID markers.
+        Ok(false)
+    }
+
+    fn extract_marker(&self, resource: &Self::Item) -> String {
+        resource.id().to_string()
+    }
+
+    async fn fetch_chunk(
+        &self,
+        limit: Option<usize>,
+        marker: Option<String>,
+    ) -> Result<Vec<Self::Item>> {
+        let query = self.query.with_marker_and_limit(limit, marker);
+        Ok(api::list_migrations(&self.session, &query)
+            .await?
+            .into_iter()
+            .map(Migration::new)
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use osauth::NoAuth;
+
+    use super::super::super::session::Session;
+    use super::MigrationQuery;
+
+    #[tokio::test]
+    async fn test_migration_query_filters() {
+        let auth = NoAuth::new("http://127.0.0.1:8774/v2.1").unwrap();
+        let query = MigrationQuery::new(Session::new(auth).await.unwrap())
+            .with_status("running")
+            .with_host("compute1")
+            .with_migration_type("evacuation")
+            .with_source_compute("compute2");
+        let params: Vec<_> = query
+            .query
+            .0
+            .iter()
+            .map(|(k, v)| (k.as_str(), v.as_str()))
+            .collect();
+        assert_eq!(
+            params,
+            vec![
+                ("status", "running"),
+                ("host", "compute1"),
+                ("migration_type", "evacuation"),
+                ("source_compute", "compute2"),
+            ]
+        );
+    }
+}
@@ -18,17 +18,29 @@ mod api;
 mod block_device_mapping;
 mod flavors;
 mod keypairs;
+mod migrations;
 mod protocol;
+mod quota_classes;
+mod server_groups;
 mod servers;
 
+pub(crate) use self::api::{
+    get_hypervisor_statistics, get_tenant_usage, get_version_details, list_tenant_usages,
+};
 pub use self::block_device_mapping::{BlockDevice, BlockDeviceDestinationType, BlockDeviceSource};
 pub use self::flavors::{DetailedFlavorQuery, Flavor, FlavorQuery, FlavorSummary};
 pub use self::keypairs::{KeyPair, KeyPairQuery, NewKeyPair};
+pub use self::migrations::{Migration, MigrationQuery};
 pub use self::protocol::{
-    AddressType, KeyPairType, RebootType, ServerAddress, ServerFlavor, ServerPowerState,
-    ServerSortKey, ServerStatus,
+    AddressType, ConsoleProtocol, ConsoleType, HypervisorStatistics, KeyPairType, RebootType,
+    RemoteConsole, ServerAddress, ServerFlavor, ServerPowerState, ServerSortKey, ServerStatus,
+    ServerUsage, TenantUsage, VersionDetails, VolumeAttachment,
 };
+pub use self::quota_classes::QuotaClass;
+pub use self::server_groups::{NewServerGroup, ServerGroup};
+pub(crate) use self::servers::{aggregate_usage, update_metadata_many};
 pub use self::servers::{
-    DetailedServerQuery, NewServer, Server, ServerCreationWaiter, ServerNIC, ServerQuery,
-    ServerStatusWaiter, ServerSummary,
+    DetailedServerQuery, NewServer, ResourceTotals, Server, ServerBatchError, ServerBatchWaiter,
+    ServerCreationWaiter, ServerData, ServerNIC, ServerQuery, ServerStatusWaiter, ServerSummary,
+    ServerWaiter,
 };
@@ -20,10 +20,11 @@
 use std::collections::HashMap;
 use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
 
-use chrono::{DateTime, FixedOffset};
-use osauth::common::{empty_as_default, IdAndName, Ref};
+use chrono::{DateTime, FixedOffset, NaiveDateTime, Utc};
+use osauth::common::{empty_as_default, IdAndName, Link, Ref};
 use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 
+use super::super::common::ApiVersion;
 use super::BlockDevice;
 
 protocol_enum! {
@@ -86,15 +87,63 @@ protocol_enum! {
     }
 }
 
-protocol_enum! {
-    #[doc = "Possible power states."]
-    enum ServerPowerState: u8 {
-        NoState = 0,
-        Running = 1,
-        Paused = 3,
-        Shutdown = 4,
-        Crashed = 6,
-        Suspended = 7
+/// Possible power states.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ServerPowerState {
+    NoState,
+    Running,
+    Paused,
+    Shutdown,
+    Crashed,
+    Suspended,
+    /// A power state not known to this library.
+    Other(u8),
+}
+
+impl From<u8> for ServerPowerState {
+    fn from(value: u8) -> ServerPowerState {
+        match value {
+            0 => ServerPowerState::NoState,
+            1 => ServerPowerState::Running,
+            3 => ServerPowerState::Paused,
+            4 => ServerPowerState::Shutdown,
+            6 => ServerPowerState::Crashed,
+            7 => ServerPowerState::Suspended,
+            other => ServerPowerState::Other(other),
+        }
+    }
+}
+
+impl From<ServerPowerState> for u8 {
+    fn from(value: ServerPowerState) -> u8 {
+        match value {
+            ServerPowerState::NoState => 0,
+            ServerPowerState::Running => 1,
+            ServerPowerState::Paused => 3,
+            ServerPowerState::Shutdown => 4,
+            ServerPowerState::Crashed => 6,
+            ServerPowerState::Suspended => 7,
+            ServerPowerState::Other(other) => other,
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for ServerPowerState {
+    fn deserialize<D>(deserializer: D) -> Result<ServerPowerState, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Ok(u8::deserialize(deserializer)?.into())
+    }
+}
+
+impl Serialize for ServerPowerState {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        u8::from(*self).serialize(serializer)
     }
 }
 
@@ -122,16 +171,37 @@ protocol_enum! {
     }
 }
 
+protocol_enum! {
+    #[doc = "Protocol of a remote console."]
+    enum ConsoleProtocol {
+        Rdp = "rdp",
+        Serial = "serial",
+        Spice = "spice",
+        Vnc = "vnc"
+    }
+}
+
+protocol_enum! {
+    #[doc = "Type of a remote console."]
+    enum ConsoleType {
+        NoVnc = "novnc",
+        RdpHtml5 = "rdp-html5",
+        Serial = "serial",
+        SpiceHtml5 = "spice-html5",
+        XvpVnc = "xvpvnc"
+    }
+}
+
 /// Address of a server.
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct ServerAddress {
     /// IP (v4 of v6) address.
     pub addr: IpAddr,
     /// MAC address (if available).
-    #[serde(rename = "OS-EXT-IPS-MAC:mac_addr", default)]
+    #[serde(rename(deserialize = "OS-EXT-IPS-MAC:mac_addr"), default)]
     pub mac_addr: Option<String>,
     /// Address type (if known).
-    #[serde(rename = "OS-EXT-IPS:type", default)]
+    #[serde(rename(deserialize = "OS-EXT-IPS:type"), default)]
     pub addr_type: Option<AddressType>,
 }
 
@@ -141,7 +211,7 @@ pub struct ExtraSpecsRoot {
 }
 
 /// A summary information of a flavor used for a server.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 pub struct ServerFlavor {
     /// Ephemeral disk size in GiB.
     pub ephemeral_size: u64,
@@ -195,6 +265,8 @@ pub struct Server {
     pub availability_zone: String,
     #[serde(rename = "created")]
     pub created_at: DateTime<FixedOffset>,
+    #[serde(default)]
+    pub deleted_at: Option<DateTime<FixedOffset>>,
     #[serde(deserialize_with = "empty_as_default", default)]
     pub description: Option<String>,
     // TODO(dtantsur): flavor in newer versions
@@ -211,16 +283,381 @@ pub struct Server {
     pub instance_name: Option<String>,
     #[serde(rename = "key_name", deserialize_with = "empty_as_default", default)]
     pub key_pair_name: Option<String>,
+    #[serde(default)]
+    pub links: Vec<Link>,
     pub name: String,
     #[serde(default)]
     pub metadata: HashMap<String, String>,
     pub status: ServerStatus,
     #[serde(rename = "OS-EXT-STS:power_state", default)]
     pub power_state: ServerPowerState,
+    #[serde(default)]
+    pub progress: Option<u8>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(rename = "OS-EXT-STS:task_state", default)]
+    pub task_state: Option<String>,
     pub tenant_id: String,
     #[serde(rename = "updated")]
     pub updated_at: DateTime<FixedOffset>,
     pub user_id: String,
+    #[serde(flatten)]
+    pub extra_attributes: serde_json::Map<String, serde_json::Value>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct Migration {
+    pub id: u64,
+    pub status: String,
+    #[serde(default)]
+    pub migration_type: Option<String>,
+    // The deployment-wide list uses instance_uuid, the per-server one server_uuid.
+    #[serde(rename = "instance_uuid", alias = "server_uuid")]
+    pub server_id: String,
+    #[serde(default)]
+    pub source_compute: Option<String>,
+    #[serde(default)]
+    pub source_node: Option<String>,
+    #[serde(default)]
+    pub dest_compute: Option<String>,
+    #[serde(default)]
+    pub dest_host: Option<String>,
+    #[serde(default)]
+    pub dest_node: Option<String>,
+    #[serde(default)]
+    pub old_instance_type_id: Option<u64>,
+    #[serde(default)]
+    pub new_instance_type_id: Option<u64>,
+    #[serde(default)]
+    pub memory_total_bytes: Option<u64>,
+    #[serde(default)]
+    pub memory_processed_bytes: Option<u64>,
+    #[serde(default)]
+    pub memory_remaining_bytes: Option<u64>,
+    #[serde(default)]
+    pub disk_total_bytes: Option<u64>,
+    #[serde(default)]
+    pub disk_processed_bytes: Option<u64>,
+    #[serde(default)]
+    pub disk_remaining_bytes: Option<u64>,
+    pub created_at: NaiveDateTime,
+    #[serde(default)]
+    pub updated_at: Option<NaiveDateTime>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct MigrationsRoot {
+    pub migrations: Vec<Migration>,
+}
+
+/// A remote console of a server.
+#[derive(Clone, Debug, Deserialize)]
+pub struct RemoteConsole {
+    /// Console protocol.
+    pub protocol: ConsoleProtocol,
+    /// Console type.
+    #[serde(rename = "type")]
+    pub console_type: ConsoleType,
+    /// URL to connect to the console.
+    pub url: String,
+    /// When the console URL was received.
+    #[serde(skip, default = "Utc::now")]
+    pub received_at: DateTime<Utc>,
+}
+
+/// A volume attached to a server.
+#[derive(Clone, Debug, Deserialize)]
+pub struct VolumeAttachment {
+    /// Whether the volume is deleted together with the server.
+    ///
+    /// Only available with compute API version 2.79 or newer.
+    #[serde(default)]
+    pub delete_on_termination: Option<bool>,
+    /// Device name inside the server (if known).
+    #[serde(default)]
+    pub device: Option<String>,
+    /// Attachment ID.
+    pub id: String,
+    /// ID of the server.
+    #[serde(rename = "serverId")]
+    pub server_id: String,
+    /// ID of the attached volume.
+    #[serde(rename = "volumeId")]
+    pub volume_id: String,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct VolumeAttachmentCreate {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delete_on_termination: Option<bool>,
+    #[serde(rename = "volumeId")]
+    pub volume_id: String,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct VolumeAttachmentCreateRoot {
+    #[serde(rename = "volumeAttachment")]
+    pub volume_attachment: VolumeAttachmentCreate,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct VolumeAttachmentRoot {
+    #[serde(rename = "volumeAttachment")]
+    pub volume_attachment: VolumeAttachment,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct RemoteConsoleCreate {
+    pub protocol: ConsoleProtocol,
+    #[serde(rename = "type")]
+    pub console_type: ConsoleType,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct RemoteConsoleCreateRoot {
+    pub remote_console: RemoteConsoleCreate,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct RemoteConsoleRoot {
+    pub remote_console: RemoteConsole,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct ConsoleRequest {
+    #[serde(rename = "type")]
+    pub console_type: ConsoleType,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct Console {
+    #[serde(rename = "type")]
+    pub console_type: ConsoleType,
+    pub url: String,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct ConsoleRoot {
+    pub console: Console,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct ConsoleOutputRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub length: Option<u32>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct ConsoleOutputRoot {
+    #[serde(default)]
+    pub output: Option<String>,
+}
+
+/// Aggregated resource statistics of all hypervisors.
+#[derive(Clone, Copy, Debug, Deserialize)]
+pub struct HypervisorStatistics {
+    /// Number of hypervisors.
+    pub count: u64,
+    /// Total local disk size in GiB.
+    pub local_gb: u64,
+    /// Used local disk size in GiB.
+    pub local_gb_used: u64,
+    /// Total RAM size in MiB.
+    pub memory_mb: u64,
+    /// Used RAM size in MiB.
+    pub memory_mb_used: u64,
+    /// Number of running servers.
+    pub running_vms: u64,
+    /// Total number of VCPUs.
+    pub vcpus: u64,
+    /// Number of used VCPUs.
+    pub vcpus_used: u64,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct HypervisorStatisticsRoot {
+    pub hypervisor_statistics: HypervisorStatistics,
+}
+
+/// Usage of a single server within a reporting period.
+#[derive(Clone, Debug, Deserialize)]
+pub struct ServerUsage {
+    /// Server ID.
+    pub instance_id: String,
+    /// Server name.
+    pub name: String,
+    /// Name of the flavor.
+    pub flavor: String,
+    /// Server state (e.g. `active` or `terminated`).
+    pub state: String,
+    /// Hours the server existed within the period.
+    pub hours: f64,
+    /// Number of VCPUs.
+    pub vcpus: u64,
+    /// RAM size in MiB.
+    pub memory_mb: u64,
+    /// Root disk size in GiB.
+    pub local_gb: u64,
+    /// Server uptime in seconds.
+    pub uptime: u64,
+    /// When the server was started (UTC).
+    pub started_at: NaiveDateTime,
+    /// When the server was deleted (UTC, if it was).
+    #[serde(default)]
+    pub ended_at: Option<NaiveDateTime>,
+    /// ID of the project owning the server.
+    pub tenant_id: String,
+}
+
+/// Usage of a project within a reporting period.
+///
+/// All values are zero if the project had no servers within the period.
+#[derive(Clone, Debug, Deserialize)]
+pub struct TenantUsage {
+    /// Project ID.
+    #[serde(default)]
+    pub tenant_id: String,
+    /// Beginning of the reported period (UTC, if any usage was reported).
+    #[serde(default)]
+    pub start: Option<NaiveDateTime>,
+    /// End of the reported period (UTC, if any usage was reported).
+    #[serde(default)]
+    pub stop: Option<NaiveDateTime>,
+    /// Total hours of all servers.
+    #[serde(default)]
+    pub total_hours: f64,
+    /// Total VCPU usage in VCPU-hours.
+    #[serde(default)]
+    pub total_vcpus_usage: f64,
+    /// Total RAM usage in MiB-hours.
+    #[serde(default)]
+    pub total_memory_mb_usage: f64,
+    /// Total local disk usage in GiB-hours.
+    #[serde(default)]
+    pub total_local_gb_usage: f64,
+    /// Usage of each server.
+    #[serde(default)]
+    pub server_usages: Vec<ServerUsage>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct TenantUsageRoot {
+    pub tenant_usage: TenantUsage,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct TenantUsagesRoot {
+    pub tenant_usages: Vec<TenantUsage>,
+}
+
+/// Limits of a quota set.
+///
+/// `-1` means unlimited.
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct QuotaSet {
+    #[serde(default)]
+    pub cores: i64,
+    #[serde(default)]
+    pub instances: i64,
+    #[serde(default)]
+    pub key_pairs: i64,
+    #[serde(default)]
+    pub metadata_items: i64,
+    #[serde(default)]
+    pub ram: i64,
+    #[serde(default)]
+    pub server_group_members: i64,
+    #[serde(default)]
+    pub server_groups: i64,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct QuotaClassSetRoot {
+    pub quota_class_set: QuotaSet,
+}
+
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct QuotaSetUpdate {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cores: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub instances: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub key_pairs: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata_items: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ram: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub server_group_members: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub server_groups: Option<i64>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct QuotaClassSetUpdateRoot {
+    pub quota_class_set: QuotaSetUpdate,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct MetadataRoot {
+    pub metadata: HashMap<String, String>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct MetadataItemRoot {
+    pub meta: HashMap<String, String>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct TagsRoot {
+    pub tags: Vec<String>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct ServerGroup {
+    pub id: String,
+    #[serde(default)]
+    pub members: Vec<String>,
+    pub name: String,
+    #[serde(default)]
+    pub policies: Vec<String>,
+    #[serde(default)]
+    pub policy: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct ServerGroupRoot {
+    pub server_group: ServerGroup,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct ServerGroupsRoot {
+    pub server_groups: Vec<ServerGroup>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct ServerGroupCreate {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub policy: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub policies: Vec<String>,
+}
+
+impl ServerGroupCreate {
+    pub fn new(name: String, policy: String) -> ServerGroupCreate {
+        ServerGroupCreate {
+            name,
+            policy: Some(policy),
+            policies: Vec::new(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct ServerGroupCreateRoot {
+    pub server_group: ServerGroupCreate,
 }
 
 #[derive(Clone, Debug, Deserialize)]
@@ -244,6 +681,7 @@ pub enum ServerNetwork {
     Network { uuid: String },
     Port { port: String },
     FixedIp { fixed_ip: Ipv4Addr },
+    NetworkFixedIp { uuid: String, fixed_ip: IpAddr },
 }
 
 #[derive(Clone, Debug, Serialize)]
@@ -260,29 +698,66 @@ pub struct ServerCreate {
     pub config_drive: Option<bool>,
     pub flavorRef: String,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub host: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hypervisor_hostname: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub imageRef: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub key_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_count: Option<u32>,
     #[serde(skip_serializing_if = "HashMap::is_empty")]
     pub metadata: HashMap<String, String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_count: Option<u32>,
     pub name: String,
     pub networks: Vec<ServerNetwork>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub return_reservation_id: Option<bool>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub security_groups: Vec<ServerSecurityGroup>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub trusted_image_certificates: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub user_data: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub availability_zone: Option<String>,
 }
 
+#[derive(Clone, Debug, Serialize)]
+pub struct ServerSecurityGroup {
+    pub name: String,
+}
+
 #[derive(Clone, Debug, Serialize)]
 pub struct ServerCreateRoot {
     pub server: ServerCreate,
 }
 
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct ServerUpdate {
+    #[serde(rename = "accessIPv4", skip_serializing_if = "Option::is_none")]
+    pub access_ipv4: Option<String>,
+    #[serde(rename = "accessIPv6", skip_serializing_if = "Option::is_none")]
+    pub access_ipv6: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct ServerUpdateRoot {
+    pub server: ServerUpdate,
+}
+
 #[derive(Clone, Debug, Deserialize)]
 pub struct CreatedServerRoot {
     pub server: Ref,
 }
 
+#[derive(Clone, Debug, Deserialize)]
+pub struct ReservationRoot {
+    pub reservation_id: String,
+}
+
 #[derive(Clone, Debug, Deserialize)]
 pub struct Flavor {
     #[serde(rename = "OS-FLV-EXT-DATA:ephemeral", default)]
@@ -298,6 +773,8 @@ pub struct Flavor {
         default = "default_flavor_is_public"
     )]
     pub is_public: bool,
+    #[serde(default)]
+    pub links: Vec<Link>,
     pub name: String,
     pub ram: u64,
     pub rxtx_factor: f32,
@@ -382,3 +859,675 @@ impl Default for ServerPowerState {
 fn default_flavor_is_public() -> bool {
     true
 }
+
+/// Details of the compute API version in use.
+#[derive(Clone, Debug, Deserialize)]
+pub struct VersionDetails {
+    /// Version ID (e.g. `v2.1`).
+    pub id: String,
+    /// Version status (e.g. `CURRENT`).
+    pub status: String,
+    /// Maximum supported microversion, if microversions are supported.
+    #[serde(deserialize_with = "empty_as_default", default)]
+    pub version: Option<ApiVersion>,
+    /// Minimum supported microversion, if microversions are supported.
+    #[serde(deserialize_with = "empty_as_default", default)]
+    pub min_version: Option<ApiVersion>,
+    /// Last update date and time of this version.
+    pub updated: DateTime<FixedOffset>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct VersionRoot {
+    pub version: VersionDetails,
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::super::common::ApiVersion;
+    use std::collections::HashMap;
+
+    use chrono::NaiveDate;
+
+    use super::{
+        ConsoleOutputRoot, ConsoleProtocol, ConsoleRoot, ConsoleType, FlavorRoot,
+        FlavorsDetailRoot, HypervisorStatisticsRoot, MetadataItemRoot, MetadataRoot,
+        MigrationsRoot, RemoteConsoleCreate, RemoteConsoleCreateRoot, RemoteConsoleRoot,
+        ReservationRoot, ServerCreate, ServerCreateRoot, ServerGroupRoot, ServerGroupsRoot,
+        ServerPowerState, ServerSecurityGroup, ServerStatus, ServerUpdate, ServerUpdateRoot,
+        ServersDetailRoot, TagsRoot, TenantUsageRoot, VersionRoot, VolumeAttachmentCreate,
+        VolumeAttachmentCreateRoot, VolumeAttachmentRoot,
+    };
+
+    const ONE_VERSION_RESPONSE: &str = r#"
+    {
+        "version": {
+            "id": "v2.1",
+            "links": [
+                {
+                    "href": "http://openstack.example.com/v2.1/",
+                    "rel": "self"
+                },
+                {
+                    "href": "http://docs.openstack.org/",
+                    "rel": "describedby",
+                    "type": "text/html"
+                }
+            ],
+            "media-types": [
+                {
+                    "base": "application/json",
+                    "type": "application/vnd.openstack.compute+json;version=2.1"
+                }
+            ],
+            "status": "CURRENT",
+            "version": "2.87",
+            "min_version": "2.1",
+            "updated": "2013-07-23T11:33:21Z"
+        }
+    }"#;
+
+    #[test]
+    fn test_server_group_parse() {
+        let root: ServerGroupRoot = serde_json::from_value(serde_json::json!({
+            "server_group": {
+                "id": "5bbcc3c4-1da2-4437-a48a-66f15b1b13f9",
+                "name": "test",
+                "policies": ["anti-affinity"],
+                "members": [
+                    "9d4a8b48-8dfa-4d72-8e2d-0e4a2fb3e7a4",
+                    "0b5e1f53-3e53-4a29-8d4e-3ad0a4b10a6e"
+                ],
+                "metadata": {}
+            }
+        }))
+        .unwrap();
+        assert_eq!(root.server_group.name, "test");
+        assert_eq!(root.server_group.policies, vec!["anti-affinity"]);
+        assert_eq!(root.server_group.members.len(), 2);
+        assert!(root.server_group.policy.is_none());
+    }
+
+    #[test]
+    fn test_server_groups_parse_policy() {
+        let root: ServerGroupsRoot = serde_json::from_value(serde_json::json!({
+            "server_groups": [{
+                "id": "5bbcc3c4-1da2-4437-a48a-66f15b1b13f9",
+                "name": "test",
+                "policy": "soft-affinity",
+                "rules": {},
+                "members": []
+            }]
+        }))
+        .unwrap();
+        assert_eq!(root.server_groups.len(), 1);
+        assert_eq!(
+            root.server_groups[0].policy.as_deref(),
+            Some("soft-affinity")
+        );
+        assert!(root.server_groups[0].policies.is_empty());
+    }
+
+    #[test]
+    fn test_migrations_parse() {
+        let root: MigrationsRoot = serde_json::from_value(serde_json::json!({
+            "migrations": [
+                {
+                    "created_at": "2016-06-23T14:42:02.000000",
+                    "dest_compute": "compute20",
+                    "dest_host": "5.6.7.8",
+                    "dest_node": "node20",
+                    "id": 1,
+                    "instance_uuid": "8600d31b-d1a1-4632-b2ff-45c2be1a70ff",
+                    "new_instance_type_id": 1,
+                    "old_instance_type_id": 1,
+                    "source_compute": "compute10",
+                    "source_node": "node10",
+                    "status": "done",
+                    "updated_at": "2016-06-23T14:42:02.000000",
+                    "migration_type": "live-migration"
+                },
+                {
+                    "created_at": "2016-06-23T13:42:02.000000",
+                    "dest_compute": "compute21",
+                    "dest_host": "5.6.7.8",
+                    "dest_node": "node21",
+                    "id": 2,
+                    "instance_uuid": "8600d31b-d1a1-4632-b2ff-45c2be1a70ff",
+                    "new_instance_type_id": 1,
+                    "old_instance_type_id": 1,
+                    "source_compute": "compute20",
+                    "source_node": "node20",
+                    "status": "running",
+                    "updated_at": null
+                }
+            ]
+        }))
+        .unwrap();
+        assert_eq!(root.migrations.len(), 2);
+        assert_eq!(
+            root.migrations[0].migration_type.as_deref(),
+            Some("live-migration")
+        );
+        assert_eq!(
+            root.migrations[0].server_id,
+            "8600d31b-d1a1-4632-b2ff-45c2be1a70ff"
+        );
+        assert_eq!(root.migrations[1].status, "running");
+        assert!(root.migrations[1].migration_type.is_none());
+        assert!(root.migrations[1].updated_at.is_none());
+    }
+
+    #[test]
+    fn test_server_migrations_parse() {
+        let root: MigrationsRoot = serde_json::from_value(serde_json::json!({
+            "migrations": [
+                {
+                    "created_at": "2016-01-29T13:42:02.000000",
+                    "dest_compute": "compute2",
+                    "dest_host": "1.2.3.4",
+                    "dest_node": "node2",
+                    "id": 1,
+                    "server_uuid": "4cfba335-03d8-49b2-8c52-e69043d1e8fe",
+                    "source_compute": "compute1",
+                    "source_node": "node1",
+                    "status": "running",
+                    "memory_total_bytes": 123456,
+                    "memory_processed_bytes": 12345,
+                    "memory_remaining_bytes": 111111,
+                    "disk_total_bytes": 234567,
+                    "disk_processed_bytes": 23456,
+                    "disk_remaining_bytes": 211111,
+                    "updated_at": "2016-01-29T13:42:02.000000"
+                }
+            ]
+        }))
+        .unwrap();
+        assert_eq!(root.migrations.len(), 1);
+        let migration = &root.migrations[0];
+        assert_eq!(migration.id, 1);
+        assert_eq!(migration.status, "running");
+        assert_eq!(migration.server_id, "4cfba335-03d8-49b2-8c52-e69043d1e8fe");
+        assert_eq!(migration.dest_compute.as_deref(), Some("compute2"));
+        assert_eq!(migration.memory_processed_bytes, Some(12345));
+        assert_eq!(migration.disk_remaining_bytes, Some(211111));
+        assert!(migration.migration_type.is_none());
+    }
+
+    #[test]
+    fn test_version_details_parse() {
+        let root: VersionRoot = serde_json::from_str(ONE_VERSION_RESPONSE).unwrap();
+        assert_eq!(root.version.id, "v2.1");
+        assert_eq!(root.version.status, "CURRENT");
+        assert_eq!(root.version.version, Some(ApiVersion(2, 87)));
+        assert_eq!(root.version.min_version, Some(ApiVersion(2, 1)));
+    }
+
+    #[test]
+    fn test_version_details_no_microversions() {
+        let root: VersionRoot = serde_json::from_str(
+            r#"{"version": {"id": "v2.0", "status": "SUPPORTED", "version": "",
+                "min_version": "", "updated": "2011-01-21T11:33:21Z"}}"#,
+        )
+        .unwrap();
+        assert_eq!(root.version.version, None);
+        assert_eq!(root.version.min_version, None);
+    }
+
+    fn server_create(trusted_image_certificates: Vec<String>) -> ServerCreate {
+        ServerCreate {
+            block_devices: Vec::new(),
+            config_drive: None,
+            flavorRef: "1".into(),
+            host: None,
+            hypervisor_hostname: None,
+            imageRef: Some("cirros".into()),
+            key_name: None,
+            max_count: None,
+            metadata: HashMap::new(),
+            min_count: None,
+            name: "test".into(),
+            networks: Vec::new(),
+            return_reservation_id: None,
+            security_groups: Vec::new(),
+            trusted_image_certificates,
+            user_data: None,
+            availability_zone: None,
+        }
+    }
+
+    #[test]
+    fn test_server_create_trusted_image_certificates() {
+        let body = serde_json::to_value(server_create(Vec::new())).unwrap();
+        assert!(body.get("trusted_image_certificates").is_none());
+
+        let body = serde_json::to_value(server_create(vec!["cert-1".into()])).unwrap();
+        assert_eq!(
+            body["trusted_image_certificates"],
+            serde_json::json!(["cert-1"])
+        );
+    }
+
+    #[test]
+    fn test_server_create_forced_host() {
+        let mut request = server_create(Vec::new());
+        request.availability_zone = Some("nova:compute-1:node-1".into());
+        let body = serde_json::to_value(request).unwrap();
+        assert_eq!(body["availability_zone"], "nova:compute-1:node-1");
+    }
+
+    #[test]
+    fn test_server_create_security_groups() {
+        let body = serde_json::to_value(server_create(Vec::new())).unwrap();
+        assert!(body.get("security_groups").is_none());
+
+        let mut request = server_create(Vec::new());
+        request.security_groups = vec![
+            ServerSecurityGroup {
+                name: "default".into(),
+            },
+            ServerSecurityGroup { name: "web".into() },
+        ];
+        let body = serde_json::to_value(request).unwrap();
+        assert_eq!(
+            body["security_groups"],
+            serde_json::json!([{"name": "default"}, {"name": "web"}])
+        );
+    }
+
+    #[test]
+    fn test_server_create_host() {
+        let body = serde_json::to_value(server_create(Vec::new())).unwrap();
+        assert!(body.get("host").is_none());
+        assert!(body.get("hypervisor_hostname").is_none());
+
+        let mut request = server_create(Vec::new());
+        request.host = Some("compute-1".into());
+        request.hypervisor_hostname = Some("node-1".into());
+        let body = serde_json::to_value(ServerCreateRoot { server: request }).unwrap();
+        assert_eq!(body["server"]["host"], "compute-1");
+        assert_eq!(body["server"]["hypervisor_hostname"], "node-1");
+        assert!(body["server"].get("availability_zone").is_none());
+    }
+
+    #[test]
+    fn test_server_create_reservation() {
+        let body = serde_json::to_value(server_create(Vec::new())).unwrap();
+        assert!(body.get("min_count").is_none());
+        assert!(body.get("max_count").is_none());
+        assert!(body.get("return_reservation_id").is_none());
+
+        let mut request = server_create(Vec::new());
+        request.min_count = Some(3);
+        request.max_count = Some(3);
+        request.return_reservation_id = Some(true);
+        let body = serde_json::to_value(ServerCreateRoot { server: request }).unwrap();
+        assert_eq!(body["server"]["min_count"], 3);
+        assert_eq!(body["server"]["max_count"], 3);
+        assert_eq!(body["server"]["return_reservation_id"], true);
+
+        let root: ReservationRoot =
+            serde_json::from_value(serde_json::json!({"reservation_id": "r-3fhpjulh"})).unwrap();
+        assert_eq!(root.reservation_id, "r-3fhpjulh");
+    }
+
+    #[test]
+    fn test_tenant_usage_parse() {
+        let root: TenantUsageRoot = serde_json::from_value(serde_json::json!({
+            "tenant_usage": {
+                "server_usages": [{
+                    "ended_at": null,
+                    "flavor": "m1.tiny",
+                    "hours": 1.0,
+                    "instance_id": "1f1deceb-17b5-4c04-84c7-e0d4499c8fe0",
+                    "local_gb": 1,
+                    "memory_mb": 512,
+                    "name": "instance-2",
+                    "started_at": "2012-10-08T20:10:44.541277",
+                    "state": "active",
+                    "tenant_id": "6f70656e737461636b20342065766572",
+                    "uptime": 3600,
+                    "vcpus": 1
+                }],
+                "start": "2012-10-08T20:10:44.587336",
+                "stop": "2012-10-08T21:10:44.587336",
+                "tenant_id": "6f70656e737461636b20342065766572",
+                "total_hours": 1.0,
+                "total_local_gb_usage": 1.0,
+                "total_memory_mb_usage": 512.0,
+                "total_vcpus_usage": 1.0
+            }
+        }))
+        .unwrap();
+        let usage = root.tenant_usage;
+        assert_eq!(usage.tenant_id, "6f70656e737461636b20342065766572");
+        assert_eq!(usage.total_memory_mb_usage, 512.0);
+        assert_eq!(
+            usage.stop.unwrap(),
+            NaiveDate::from_ymd_opt(2012, 10, 8)
+                .unwrap()
+                .and_hms_micro_opt(21, 10, 44, 587336)
+                .unwrap()
+        );
+        assert_eq!(usage.server_usages.len(), 1);
+        assert_eq!(usage.server_usages[0].uptime, 3600);
+        assert!(usage.server_usages[0].ended_at.is_none());
+
+        // Projects without usage get an empty object
+        let root: TenantUsageRoot =
+            serde_json::from_value(serde_json::json!({"tenant_usage": {}})).unwrap();
+        assert!(root.tenant_usage.start.is_none());
+        assert_eq!(root.tenant_usage.total_hours, 0.0);
+        assert!(root.tenant_usage.server_usages.is_empty());
+    }
+
+    #[test]
+    fn test_remote_console() {
+        let body = RemoteConsoleCreateRoot {
+            remote_console: RemoteConsoleCreate {
+                protocol: ConsoleProtocol::Vnc,
+                console_type: ConsoleType::NoVnc,
+            },
+        };
+        assert_eq!(
+            serde_json::to_value(body).unwrap(),
+            serde_json::json!({"remote_console": {"protocol": "vnc", "type": "novnc"}})
+        );
+
+        let root: RemoteConsoleRoot = serde_json::from_value(serde_json::json!({
+            "remote_console": {
+                "protocol": "vnc",
+                "type": "novnc",
+                "url": "http://example.com:6080/vnc_auto.html?path=%3Ftoken%3Dabcd"
+            }
+        }))
+        .unwrap();
+        assert_eq!(root.remote_console.protocol, ConsoleProtocol::Vnc);
+        assert_eq!(root.remote_console.console_type, ConsoleType::NoVnc);
+        assert_eq!(
+            root.remote_console.url,
+            "http://example.com:6080/vnc_auto.html?path=%3Ftoken%3Dabcd"
+        );
+    }
+
+    #[test]
+    fn test_serial_console() {
+        let root: ConsoleRoot = serde_json::from_value(serde_json::json!({
+            "console": {
+                "type": "serial",
+                "url": "ws://127.0.0.1:6083/?token=abcd"
+            }
+        }))
+        .unwrap();
+        assert_eq!(root.console.console_type, ConsoleType::Serial);
+        assert_eq!(root.console.url, "ws://127.0.0.1:6083/?token=abcd");
+    }
+
+    fn flavor_json(swap: serde_json::Value) -> serde_json::Value {
+        serde_json::json!({
+            "flavor": {
+                "OS-FLV-EXT-DATA:ephemeral": 10,
+                "disk": 20,
+                "id": "1",
+                "name": "m1.small",
+                "ram": 2048,
+                "rxtx_factor": 1.0,
+                "swap": swap,
+                "vcpus": 1
+            }
+        })
+    }
+
+    #[test]
+    fn test_flavor_swap_empty() {
+        let root: FlavorRoot = serde_json::from_value(flavor_json("".into())).unwrap();
+        assert_eq!(root.flavor.swap, 0);
+        assert_eq!(root.flavor.ephemeral, 10);
+    }
+
+    #[test]
+    fn test_flavor_swap_set() {
+        let root: FlavorRoot = serde_json::from_value(flavor_json(512.into())).unwrap();
+        assert_eq!(root.flavor.swap, 512);
+    }
+
+    #[test]
+    fn test_flavors_detail_parse() {
+        let flavor = flavor_json(0.into())["flavor"].clone();
+        let root: FlavorsDetailRoot = serde_json::from_value(serde_json::json!({
+            "flavors": [flavor],
+            "flavors_links": [
+                {"href": "http://compute/v2.1/flavors/detail?marker=1", "rel": "next"}
+            ]
+        }))
+        .unwrap();
+        assert_eq!(root.flavors.len(), 1);
+        let flavor = &root.flavors[0];
+        assert_eq!(flavor.vcpus, 1);
+        assert_eq!(flavor.ram, 2048);
+        assert_eq!(flavor.disk, 20);
+        assert!(flavor.is_public);
+    }
+
+    #[test]
+    fn test_flavor_links() {
+        let mut json = flavor_json(0.into());
+        json["flavor"]["links"] = serde_json::json!([
+            {"href": "http://compute/v2.1/flavors/1", "rel": "self"},
+            {"href": "http://compute/flavors/1", "rel": "bookmark"}
+        ]);
+        let root: FlavorRoot = serde_json::from_value(json).unwrap();
+        assert_eq!(root.flavor.links.len(), 2);
+        assert_eq!(root.flavor.links[1].rel, "bookmark");
+        assert_eq!(
+            root.flavor.links[1].href.as_str(),
+            "http://compute/flavors/1"
+        );
+    }
+
+    #[test]
+    fn test_server_update_access_ipv4_only() {
+        let body = ServerUpdateRoot {
+            server: ServerUpdate {
+                access_ipv4: Some("192.0.2.10".into()),
+                ..ServerUpdate::default()
+            },
+        };
+        assert_eq!(
+            serde_json::to_value(body).unwrap(),
+            serde_json::json!({"server": {"accessIPv4": "192.0.2.10"}})
+        );
+    }
+
+    #[test]
+    fn test_console_output_empty() {
+        for body in &[
+            serde_json::json!({"output": ""}),
+            serde_json::json!({"output": null}),
+            serde_json::json!({}),
+        ] {
+            let root: ConsoleOutputRoot = serde_json::from_value(body.clone()).unwrap();
+            assert_eq!(root.output.unwrap_or_default(), "");
+        }
+    }
+
+    #[test]
+    fn test_volume_attachment_create() {
+        let body = VolumeAttachmentCreateRoot {
+            volume_attachment: VolumeAttachmentCreate {
+                delete_on_termination: Some(true),
+                volume_id: "a26887c6-c47b-4654-abb5-dfadf7d3f803".into(),
+            },
+        };
+        assert_eq!(
+            serde_json::to_value(&body).unwrap(),
+            serde_json::json!({
+                "volumeAttachment": {
+                    "delete_on_termination": true,
+                    "volumeId": "a26887c6-c47b-4654-abb5-dfadf7d3f803"
+                }
+            })
+        );
+        let root: VolumeAttachmentRoot = serde_json::from_value(serde_json::json!({
+            "volumeAttachment": {
+                "delete_on_termination": true,
+                "device": "/dev/vdb",
+                "id": "a26887c6-c47b-4654-abb5-dfadf7d3f803",
+                "serverId": "2aad99d3-7aa4-41e9-b4e6-3f960b115d68",
+                "tag": null,
+                "volumeId": "a26887c6-c47b-4654-abb5-dfadf7d3f803"
+            }
+        }))
+        .unwrap();
+        assert_eq!(root.volume_attachment.delete_on_termination, Some(true));
+        assert_eq!(root.volume_attachment.device.as_deref(), Some("/dev/vdb"));
+    }
+
+    #[test]
+    fn test_hypervisor_statistics() {
+        let root: HypervisorStatisticsRoot = serde_json::from_value(serde_json::json!({
+            "hypervisor_statistics": {
+                "count": 1,
+                "current_workload": 0,
+                "disk_available_least": 0,
+                "free_disk_gb": 1028,
+                "free_ram_mb": 7680,
+                "local_gb": 1028,
+                "local_gb_used": 0,
+                "memory_mb": 8192,
+                "memory_mb_used": 512,
+                "running_vms": 0,
+                "vcpus": 2,
+                "vcpus_used": 0
+            }
+        }))
+        .unwrap();
+        let stats = root.hypervisor_statistics;
+        assert_eq!(stats.count, 1);
+        assert_eq!(stats.local_gb, 1028);
+        assert_eq!(stats.memory_mb, 8192);
+        assert_eq!(stats.memory_mb_used, 512);
+        assert_eq!(stats.vcpus, 2);
+        assert_eq!(stats.running_vms, 0);
+    }
+
+    #[test]
+    fn test_metadata_parse() {
+        let root: MetadataRoot = serde_json::from_value(serde_json::json!({
+            "metadata": {"role": "web", "owner": "ops"}
+        }))
+        .unwrap();
+        assert_eq!(root.metadata.len(), 2);
+        assert_eq!(root.metadata["role"], "web");
+    }
+
+    #[test]
+    fn test_metadata_item_body() {
+        let mut meta = HashMap::new();
+        let _ = meta.insert("role".to_string(), "web".to_string());
+        assert_eq!(
+            serde_json::to_value(MetadataItemRoot { meta }).unwrap(),
+            serde_json::json!({"meta": {"role": "web"}})
+        );
+    }
+
+    #[test]
+    fn test_tags_body() {
+        let body = TagsRoot {
+            tags: vec!["web".into(), "prod".into()],
+        };
+        assert_eq!(
+            serde_json::to_value(body).unwrap(),
+            serde_json::json!({"tags": ["web", "prod"]})
+        );
+    }
+
+    #[test]
+    fn test_server_power_state() {
+        let states: Vec<ServerPowerState> =
+            serde_json::from_value(serde_json::json!([0, 1, 4, 9])).unwrap();
+        assert_eq!(
+            states,
+            vec![
+                ServerPowerState::NoState,
+                ServerPowerState::Running,
+                ServerPowerState::Shutdown,
+                ServerPowerState::Other(9)
+            ]
+        );
+        assert_eq!(
+            serde_json::to_value(ServerPowerState::Other(9)).unwrap(),
+            serde_json::json!(9)
+        );
+        assert_eq!(u8::from(ServerPowerState::Paused), 3);
+    }
+
+    #[test]
+    fn test_servers_detail_parse() {
+        let root: ServersDetailRoot = serde_json::from_value(serde_json::json!({
+            "servers": [
+                {
+                    "OS-EXT-AZ:availability_zone": "nova",
+                    "OS-EXT-STS:power_state": 1,
+                    "OS-EXT-STS:task_state": null,
+                    "OS-EXT-STS:vm_state": "active",
+                    "addresses": {
+                        "private": [
+                            {
+                                "OS-EXT-IPS-MAC:mac_addr": "aa:bb:cc:dd:ee:ff",
+                                "OS-EXT-IPS:type": "fixed",
+                                "addr": "192.168.0.3",
+                                "version": 4
+                            }
+                        ]
+                    },
+                    "config_drive": "",
+                    "created": "2020-01-01T00:00:00Z",
+                    "flavor": {
+                        "id": "1",
+                        "links": [
+                            {
+                                "href": "http://openstack.example.com/flavors/1",
+                                "rel": "bookmark"
+                            }
+                        ]
+                    },
+                    "id": "9168b536-cd40-4630-b43f-b259807c6e87",
+                    "image": "",
+                    "metadata": {"role": "web"},
+                    "name": "web-1",
+                    "status": "ACTIVE",
+                    "tenant_id": "demo",
+                    "updated": "2020-01-01T00:01:00Z",
+                    "user_id": "demo"
+                },
+                {
+                    "OS-EXT-AZ:availability_zone": "nova",
+                    "config_drive": "True",
+                    "created": "2020-01-01T00:00:00Z",
+                    "flavor": {"id": "2", "links": []},
+                    "id": "c3f14e9a-5e4b-4d6c-9d5e-1b5a3b7c2d10",
+                    "name": "db-1",
+                    "status": "ERROR",
+                    "tenant_id": "demo",
+                    "updated": "2020-01-01T00:01:00Z",
+                    "user_id": "demo"
+                }
+            ]
+        }))
+        .unwrap();
+        assert_eq!(root.servers.len(), 2);
+        let web = &root.servers[0];
+        assert_eq!(web.status, ServerStatus::Active);
+        assert_eq!(web.flavor.id, "1");
+        assert!(web.image.is_none());
+        assert_eq!(
+            web.addresses["private"][0].addr,
+            "192.168.0.3".parse::<std::net::IpAddr>().unwrap()
+        );
+        let db = &root.servers[1];
+        assert_eq!(db.status, ServerStatus::Error);
+        assert!(db.has_config_drive);
+        assert!(db.addresses.is_empty());
+    }
+}
@@ -0,0 +1,209 @@
+// Copyright 2026 Dmitry Tantsur <divius.inside@gmail.com>
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Quota class (default quota) management via Compute API.
+
+use std::collections::HashSet;
+
+use async_trait::async_trait;
+
+use super::super::common::Refresh;
+use super::super::session::Session;
+use super::super::Result;
+use super::{api, protocol};
+
+/// Structure representing a quota class.
+///
+/// The `default` quota class defines the quotas of projects without explicit quotas.
+#[derive(Clone, Debug)]
+pub struct QuotaClass {
+    session: Session,
+    name: String,
+    inner: protocol::QuotaSet,
+    dirty: HashSet<&'static str>,
+}
+
+impl QuotaClass {
+    /// Load a QuotaClass object.
+    pub(crate) async fn load<S: Into<String>>(session: Session, name: S) -> Result<QuotaClass> {
+        let name = name.into();
+        let inner = api::get_quota_class(&session, &name).await?;
+        Ok(QuotaClass {
+            session,
+            name,
+            inner,
+            dirty: HashSet::new(),
+        })
+    }
+
+    /// Quota class name.
+    #[inline]
+    pub fn name(&self) -> &String {
+        &self.name
+    }
+
+    transparent_property! {
+        #[doc = "Number of VCPUs (`-1` for unlimited)."]
+        cores: i64
+    }
+
+    update_field! {
+        #[doc = "Update the number of VCPUs."]
+        set_cores, with_cores -> cores: i64
+    }
+
+    transparent_property! {
+        #[doc = "Number of servers (`-1` for unlimited)."]
+        instances: i64
+    }
+
+    update_field! {
+        #[doc = "Update the number of servers."]
+        set_instances, with_instances -> instances: i64
+    }
+
+    transparent_property! {
+        #[doc = "Number of key pairs per user (`-1` for unlimited)."]
+        key_pairs: i64
+    }
+
+    update_field! {
+        #[doc = "Update the number of key pairs per user."]
+        set_key_pairs, with_key_pairs -> key_pairs: i64
+    }
+
+    transparent_property! {
+        #[doc = "Number of metadata items per server (`-1` for unlimited)."]
+        metadata_items: i64
+    }
+
+    update_field! {
+        #[doc = "Update the number of metadata items per server."]
+        set_metadata_items, with_metadata_items -> metadata_items: i64
+    }
+
+    transparent_property! {
+        #[doc = "RAM size in MiB (`-1` for unlimited)."]
+        ram: i64
+    }
+
+    update_field! {
+        #[doc = "Update the RAM size in MiB."]
+        set_ram, with_ram -> ram: i64
+    }
+
+    transparent_property! {
+        #[doc = "Number of servers per server group (`-1` for unlimited)."]
+        server_group_members: i64
+    }
+
+    update_field! {
+        #[doc = "Update the number of servers per server group."]
+        set_server_group_members, with_server_group_members -> server_group_members: i64
+    }
+
+    transparent_property! {
+        #[doc = "Number of server groups (`-1` for unlimited)."]
+        server_groups: i64
+    }
+
+    update_field! {
+        #[doc = "Update the number of server groups."]
+        set_server_groups, with_server_groups -> server_groups: i64
+    }
+
+    /// Whether the quota class is modified.
+    pub fn is_dirty(&self) -> bool {
+        !self.dirty.is_empty()
+    }
+
+    /// Save the changes to the quota class.
+    ///
+    /// Only the modified limits are sent. Requires admin privileges.
+    pub async fn save(&mut self) -> Result<()> {
+        let update = self.update_body();
+        self.inner = api::update_quota_class(&self.session, &self.name, update).await?;
+        self.dirty.clear();
+        Ok(())
+    }
+
+    #[allow(clippy::field_reassign_with_default)]
+    fn update_body(&self) -> protocol::QuotaSetUpdate {
+        let mut update = protocol::QuotaSetUpdate::default();
+        save_fields! {
+            self -> update: cores instances key_pairs metadata_items ram server_group_members
+                server_groups
+        };
+        update
+    }
+}
+
+#[async_trait]
+impl Refresh for QuotaClass {
+    /// Refresh the quota class.
+    async fn refresh(&mut self) -> Result<()> {
+        self.inner = api::get_quota_class(&self.session, &self.name).await?;
+        self.dirty.clear();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashSet;
+
+    use osauth::NoAuth;
+
+    use super::super::super::session::Session;
+    use super::super::protocol;
+    use super::QuotaClass;
+
+    #[tokio::test]
+    async fn test_quota_class_partial_update() {
+        let root: protocol::QuotaClassSetRoot = serde_json::from_value(serde_json::json!({
+            "quota_class_set": {
+                "cores": 20,
+                "id": "default",
+                "instances": 10,
+                "key_pairs": 100,
+                "metadata_items": 128,
+                "ram": 51200,
+                "server_group_members": 10,
+                "server_groups": 10
+            }
+        }))
+        .unwrap();
+        let auth = NoAuth::new("http://127.0.0.1:8774/v2.1").unwrap();
+        let mut quota = QuotaClass {
+            session: Session::new(auth).await.unwrap(),
+            name: "default".into(),
+            inner: root.quota_class_set,
+            dirty: HashSet::new(),
+        };
+        assert_eq!(quota.cores(), 20);
+        assert_eq!(quota.ram(), 51200);
+        assert!(!quota.is_dirty());
+
+        quota.set_instances(50);
+        quota.set_cores(-1);
+        assert!(quota.is_dirty());
+        let body = protocol::QuotaClassSetUpdateRoot {
+            quota_class_set: quota.update_body(),
+        };
+        assert_eq!(
+            serde_json::to_value(body).unwrap(),
+            serde_json::json!({"quota_class_set": {"cores": -1, "instances": 50}})
+        );
+    }
+}
@@ -0,0 +1,217 @@
+// Copyright 2026 Dmitry Tantsur <divius.inside@gmail.com>
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Server group management via Compute API.
+
+use async_trait::async_trait;
+
+use super::super::common::Refresh;
+use super::super::session::Session;
+use super::super::{ErrorKind, Result};
+use super::{api, protocol, Server};
+
+/// Structure representing a server group.
+#[derive(Clone, Debug)]
+pub struct ServerGroup {
+    session: Session,
+    inner: protocol::ServerGroup,
+}
+
+/// A request to create a server group.
+#[derive(Clone, Debug)]
+pub struct NewServerGroup {
+    session: Session,
+    inner: protocol::ServerGroupCreate,
+}
+
+impl ServerGroup {
+    /// Create a ServerGroup object.
+    pub(crate) fn new(session: Session, inner: protocol::ServerGroup) -> ServerGroup {
+        ServerGroup { session, inner }
+    }
+
+    /// Load a ServerGroup object.
+    pub(crate) async fn load<Id: AsRef<str>>(session: Session, id: Id) -> Result<ServerGroup> {
+        let inner = api::get_server_group(&session, id).await?;
+        Ok(ServerGroup::new(session, inner))
+    }
+
+    /// List all server groups.
+    pub(crate) async fn list(session: Session) -> Result<Vec<ServerGroup>> {
+        let groups = api::list_server_groups(&session).await?;
+        Ok(groups
+            .into_iter()
+            .map(|inner| ServerGroup::new(session.clone(), inner))
+            .collect())
+    }
+
+    /// Delete the server group.
+    pub async fn delete(self) -> Result<()> {
+        api::delete_server_group(&self.session, &self.inner.id).await
+    }
+
+    transparent_property! {
+        #[doc = "Server group unique ID."]
+        id: ref String
+    }
+
+    transparent_property! {
+        #[doc = "IDs of the servers in the group."]
+        members: ref Vec<String>
+    }
+
+    transparent_property! {
+        #[doc = "Server group name."]
+        name: ref String
+    }
+
+    transparent_property! {
+        #[doc = "Scheduling policies of the group (empty since API version 2.64)."]
+        policies: ref Vec<String>
+    }
+
+    /// Scheduling policy of the group (e.g. `anti-affinity`).
+    ///
+    /// Clouds before API version 2.64 report it as the only item of `policies`.
+    pub fn policy(&self) -> Option<&str> {
+        self.inner
+            .policy
+            .as_deref()
+            .or_else(|| self.inner.policies.first().map(String::as_str))
+    }
+
+    /// Fetch the servers in the group.
+    ///
+    /// Members that have been deleted in the meantime are skipped.
+    pub async fn member_servers(&self) -> Result<Vec<Server>> {
+        let mut loaded = Vec::with_capacity(self.inner.members.len());
+        for id in &self.inner.members {
+            loaded.push((id.as_str(), Server::load(self.session.clone(), id).await));
+        }
+        existing_members(&self.inner.id, loaded)
+    }
+}
+
+/// Collect the loaded members of a group, skipping the ones that no longer exist.
+fn existing_members<'a, T, I>(group_id: &str, loaded: I) -> Result<Vec<T>>
+where
+    I: IntoIterator<Item = (&'a str, Result<T>)>,
+{
+    let mut result = Vec::new();
+    for (id, item) in loaded {
+        match item {
+            Ok(item) => result.push(item),
+            Err(err) if err.kind() == ErrorKind::ResourceNotFound => {
+                debug!("Member {} of server group {} is gone", id, group_id);
+            }
+            Err(err) => return Err(err),
+        }
+    }
+    Ok(result)
+}
+
+#[async_trait]
+impl Refresh for ServerGroup {
+    /// Refresh the server group.
+    async fn refresh(&mut self) -> Result<()> {
+        self.inner = api::get_server_group(&self.session, &self.inner.id).await?;
+        Ok(())
+    }
+}
+
+impl NewServerGroup {
+    /// Start creating a server group.
+    pub(crate) fn new(session: Session, name: String, policy: String) -> NewServerGroup {
+        NewServerGroup {
+            session,
+            inner: protocol::ServerGroupCreate::new(name, policy),
+        }
+    }
+
+    /// Request creation of the server group.
+    pub async fn create(self) -> Result<ServerGroup> {
+        let inner = api::create_server_group(&self.session, self.inner).await?;
+        Ok(ServerGroup::new(self.session, inner))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use osauth::NoAuth;
+
+    use super::super::super::session::Session;
+    use super::super::super::{Error, ErrorKind};
+    use super::{existing_members, protocol, ServerGroup};
+
+    async fn new_group(members: Vec<String>, policy: Option<&str>) -> ServerGroup {
+        // Nothing listens on this port, so requests fail to connect.
+        let auth = NoAuth::new("http://127.0.0.1:1/compute").unwrap();
+        let session = Session::new(auth).await.unwrap();
+        ServerGroup::new(
+            session,
+            protocol::ServerGroup {
+                id: "group-id".into(),
+                members,
+                name: "group".into(),
+                policies: vec!["affinity".into()],
+                policy: policy.map(From::from),
+            },
+        )
+    }
+
+    #[tokio::test]
+    async fn test_server_group_policy() {
+        assert_eq!(new_group(Vec::new(), None).await.policy(), Some("affinity"));
+        assert_eq!(
+            new_group(Vec::new(), Some("soft-affinity")).await.policy(),
+            Some("soft-affinity")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_member_servers_empty() {
+        let group = new_group(Vec::new(), None).await;
+        assert!(group.member_servers().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_member_servers_propagates_errors() {
+        let group = new_group(vec!["server-id".into()], None).await;
+        let err = group.member_servers().await.unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::ProtocolError);
+    }
+
+    #[test]
+    fn test_existing_members() {
+        let loaded = vec![
+            ("srv-1", Ok("srv-1")),
+            (
+                "srv-2",
+                Err(Error::new(ErrorKind::ResourceNotFound, "gone")),
+            ),
+            ("srv-3", Ok("srv-3")),
+        ];
+        assert_eq!(
+            existing_members("group-id", loaded).unwrap(),
+            vec!["srv-1", "srv-3"]
+        );
+
+        let loaded = vec![
+            ("srv-1", Ok("srv-1")),
+            ("srv-2", Err(Error::new(ErrorKind::AccessDenied, "denied"))),
+        ];
+        let err = existing_members("group-id", loaded).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::AccessDenied);
+    }
+}
@@ -14,26 +14,33 @@
 
 //! Server management via Compute API.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::future::Future;
+use std::io::{self, Write};
 use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
 use std::time::Duration;
 
 use async_trait::async_trait;
-use chrono::{DateTime, FixedOffset};
-use futures::stream::{Stream, TryStreamExt};
+use chrono::{DateTime, FixedOffset, TimeZone, Utc};
+use futures::stream::{self, Stream, StreamExt, TryStreamExt};
 use osauth::common::IdAndName;
+use reqwest::Url;
+use serde::Serialize;
 
 use super::super::common::{
-    FlavorRef, ImageRef, KeyPairRef, NetworkRef, PortRef, ProjectRef, Refresh, ResourceIterator,
-    ResourceQuery, UserRef, VolumeRef,
+    ApiVersion, FlavorRef, ImageRef, KeyPairRef, Link, NetworkRef, Page, PortRef, ProjectRef,
+    Refresh, ResourceIterator, ResourceQuery, SecurityGroupRef, UserRef, VolumeRef,
 };
 #[cfg(feature = "image")]
 use super::super::image::Image;
+#[cfg(feature = "network")]
+use super::super::network::{FloatingIp, PortQuery};
 use super::super::session::Session;
 use super::super::utils::Query;
 use super::super::waiter::{DeletionWaiter, Waiter};
 use super::super::{Error, ErrorKind, Result, Sort};
-use super::{api, protocol, BlockDevice, KeyPair};
+use super::{api, protocol, BlockDevice, KeyPair, Migration};
 
 /// A query to server list.
 #[derive(Clone, Debug)]
@@ -41,6 +48,9 @@ pub struct ServerQuery {
     session: Session,
     query: Query,
     can_paginate: bool,
+    api_version: Option<ApiVersion>,
+    reverse: bool,
+    page_size: Option<usize>,
 }
 
 /// A detailed query to server list.
@@ -66,15 +76,90 @@ pub struct ServerSummary {
     inner: IdAndName,
 }
 
+/// Parsed server information detached from the session.
+///
+/// Created with `Server::to_data`. Unlike `Server` it cannot be used to act on the server.
+#[derive(Clone, Debug, Serialize)]
+pub struct ServerData {
+    /// IPv4 address to access the server (if provided).
+    pub access_ipv4: Option<Ipv4Addr>,
+    /// IPv6 address to access the server (if provided).
+    pub access_ipv6: Option<Ipv6Addr>,
+    /// Addresses (floating and fixed) associated with the server.
+    pub addresses: HashMap<String, Vec<protocol::ServerAddress>>,
+    /// Availability zone.
+    pub availability_zone: String,
+    /// Creation date and time.
+    pub created_at: DateTime<FixedOffset>,
+    /// Server description.
+    pub description: Option<String>,
+    /// Flavor information used to create this server.
+    pub flavor: protocol::ServerFlavor,
+    /// Whether the server was created with a config drive.
+    pub has_config_drive: bool,
+    /// Server unique ID.
+    pub id: String,
+    /// ID of the image (if any).
+    pub image_id: Option<String>,
+    /// Instance name.
+    pub instance_name: Option<String>,
+    /// Name of a key pair used with this server (if any).
+    pub key_pair_name: Option<String>,
+    /// Metadata associated with the server.
+    pub metadata: HashMap<String, String>,
+    /// Server name.
+    pub name: String,
+    /// Server power state.
+    pub power_state: protocol::ServerPowerState,
+    /// Progress of the current operation in percent (if any).
+    pub progress: Option<u8>,
+    /// Server status.
+    pub status: protocol::ServerStatus,
+    /// Current task state (if any).
+    pub task_state: Option<String>,
+    /// Last update date and time.
+    pub updated_at: DateTime<FixedOffset>,
+}
+
+/// Total resources used by a set of servers.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ResourceTotals {
+    /// RAM size in MiB.
+    pub ram_size: u64,
+    /// Root disk size in GiB.
+    pub root_size: u64,
+    /// VCPU count.
+    pub vcpu_count: u64,
+}
+
 /// Waiter for server status to change.
 #[derive(Debug)]
 pub struct ServerStatusWaiter<'server> {
     server: &'server mut Server,
     target: protocol::ServerStatus,
+    // Update time before an action that starts and ends in the target status
+    // (e.g. reboot), reset once the server is seen changing.
+    unchanged_since: Option<DateTime<FixedOffset>>,
+    // Whether the server must also have no task in progress (set for reboots).
+    idle_required: bool,
+}
+
+type ServerCheck<T> = Box<dyn Fn(&Server) -> Option<T> + Send + Sync>;
+
+/// Waiter for a server to satisfy a condition.
+///
+/// Fails if the server goes into the `ERROR` state before the condition is met.
+pub struct ServerWaiter<'server, T> {
+    server: &'server mut Server,
+    description: String,
+    check: ServerCheck<T>,
 }
 
 /// A virtual NIC of a new server.
+///
+/// More variants may be added in the future, so matching on it requires a wildcard arm.
 #[derive(Clone, Debug)]
+#[non_exhaustive]
 pub enum ServerNIC {
     /// A NIC from the given network.
     FromNetwork(NetworkRef),
@@ -82,6 +167,8 @@ pub enum ServerNIC {
     WithPort(PortRef),
     /// A NIC with the given fixed IP.
     WithFixedIp(Ipv4Addr),
+    /// A NIC from the given network with the given fixed IP.
+    FromNetworkWithFixedIp(NetworkRef, IpAddr),
 }
 
 /// A request to create a server.
@@ -95,9 +182,13 @@ pub struct NewServer {
     name: String,
     nics: Vec<ServerNIC>,
     block_devices: Vec<BlockDevice>,
+    security_groups: Option<Vec<SecurityGroupRef>>,
+    trusted_image_certificates: Vec<String>,
     user_data: Option<String>,
     config_drive: Option<bool>,
     availability_zone: Option<String>,
+    host: Option<String>,
+    hypervisor_hostname: Option<String>,
 }
 
 /// Waiter for server to be created.
@@ -106,6 +197,25 @@ pub struct ServerCreationWaiter {
     server: Server,
 }
 
+/// Waiter for several servers created in one request to become active.
+#[derive(Debug)]
+pub struct ServerBatchWaiter {
+    session: Session,
+    reservation_id: String,
+    count: usize,
+    servers: Vec<Server>,
+}
+
+/// Error returned by [ServerBatchWaiter] when not all servers became active.
+///
+/// Holds the servers seen before the failure (in any status), e.g. to delete them.
+/// Converts into [Error], so `?` works in functions returning [Result].
+#[derive(Debug)]
+pub struct ServerBatchError {
+    servers: Vec<Server>,
+    error: Error,
+}
+
 #[async_trait]
 impl Refresh for Server {
     /// Refresh the server.
@@ -140,6 +250,15 @@ impl Server {
         Server::new(session, inner).await
     }
 
+    /// Load a Server object by ID together with the raw response body.
+    pub(crate) async fn load_raw<Id: AsRef<str>>(
+        session: Session,
+        id: Id,
+    ) -> Result<(Server, serde_json::Value)> {
+        let (inner, body) = api::get_server_by_id_raw(&session, id).await?;
+        Ok((Server::new(session, inner).await?, body))
+    }
+
     transparent_property! {
         #[doc = "IPv4 address to access the server (if provided)."]
         access_ipv4: Option<Ipv4Addr>
@@ -165,28 +284,96 @@ impl Server {
         created_at: DateTime<FixedOffset>
     }
 
+    /// Time passed since the server was created.
+    ///
+    /// Computed against the local clock, so it is off by any clock skew between this machine
+    /// and the cloud.
+    pub fn age(&self) -> chrono::Duration {
+        self.age_at(Utc::now())
+    }
+
+    fn age_at(&self, now: DateTime<Utc>) -> chrono::Duration {
+        now.signed_duration_since(self.inner.created_at)
+    }
+
+    /// Bookmark URL of the server (if provided by the server).
+    ///
+    /// Unlike the `self` link, the bookmark link does not contain the API version.
+    pub fn bookmark_url(&self) -> Option<&Url> {
+        self.inner
+            .links
+            .iter()
+            .find(|link| link.rel == "bookmark")
+            .map(|link| &link.href)
+    }
+
+    transparent_property! {
+        #[doc = "Deletion date and time (only for deleted servers)."]
+        deleted_at: Option<DateTime<FixedOffset>>
+    }
+
+    /// Whether the server was deleted within the given time range (inclusive).
+    ///
+    /// Always `false` for servers that are not deleted.
+    pub fn was_deleted_between<T1, T2>(&self, since: DateTime<T1>, until: DateTime<T2>) -> bool
+    where
+        T1: TimeZone,
+        T2: TimeZone,
+    {
+        match self.inner.deleted_at {
+            Some(deleted_at) => deleted_at >= since && deleted_at <= until,
+            None => false,
+        }
+    }
+
     transparent_property! {
         #[doc = "Server description."]
         description: ref Option<String>
     }
 
+    transparent_property! {
+        #[doc = "Attributes not otherwise exposed by this object."]
+        #[doc = ""]
+        #[doc = "The contents are cloud-dependent and may include vendor extensions."]
+        extra_attributes: ref serde_json::Map<String, serde_json::Value>
+    }
+
     /// Flavor information used to create this server.
     #[inline]
     pub fn flavor(&self) -> &protocol::ServerFlavor {
         &self.flavor
     }
 
+    /// All fixed IP addresses of the server on all networks.
+    pub fn fixed_ips(&self) -> Vec<&protocol::ServerAddress> {
+        self.addresses_of_type(protocol::AddressType::Fixed)
+            .collect()
+    }
+
     /// Find a floating IP, if it exists.
     ///
     /// If multiple floating IPs exist, the first is returned.
     pub fn floating_ip(&self) -> Option<IpAddr> {
+        self.addresses_of_type(protocol::AddressType::Floating)
+            .map(|a| a.addr)
+            .next()
+    }
+
+    /// All floating IP addresses of the server.
+    pub fn floating_ips(&self) -> Vec<&protocol::ServerAddress> {
+        self.addresses_of_type(protocol::AddressType::Floating)
+            .collect()
+    }
+
+    fn addresses_of_type(
+        &self,
+        addr_type: protocol::AddressType,
+    ) -> impl Iterator<Item = &protocol::ServerAddress> {
         self.inner
             .addresses
             .values()
             .flat_map(|l| l.iter())
-            .filter(|a| a.addr_type == Some(protocol::AddressType::Floating))
-            .map(|a| a.addr)
-            .next()
+            .filter(move |a| a.addr_type == Some(addr_type))
     }
 
     transparent_property! {
@@ -252,11 +439,27 @@ impl Server {
         key_pair_name: ref Option<String>
     }
 
+    /// Links to the server.
+    pub fn links(&self) -> &[Link] {
+        &self.inner.links
+    }
+
     transparent_property! {
         #[doc = "Server name."]
         name: ref String
     }
 
+    /// List in-progress migrations of the server.
+    ///
+    /// Requires compute API version 2.23 or newer and admin privileges.
+    pub async fn migrations(&self) -> Result<Vec<Migration>> {
+        Ok(api::list_server_migrations(&self.session, &self.inner.id)
+            .await?
+            .into_iter()
+            .map(Migration::new)
+            .collect())
+    }
+
     transparent_property! {
         #[doc = "Metadata associated with the server."]
         metadata: ref HashMap<String, String>
@@ -267,16 +470,78 @@ impl Server {
         power_state: protocol::ServerPowerState
     }
 
+    transparent_property! {
+        #[doc = "Progress of the current operation in percent (if any)."]
+        progress: Option<u8>
+    }
+
     transparent_property! {
         #[doc = "Server status."]
         status: protocol::ServerStatus
     }
 
+    transparent_property! {
+        #[doc = "Tags of the server in the order returned by the Compute service."]
+        #[doc = ""]
+        #[doc = "Always empty with compute API versions older than 2.26."]
+        tags: ref Vec<String>
+    }
+
+    /// Whether the server has the given tag.
+    ///
+    /// The Compute service allows at most 50 tags per server, so this check is cheap. Use
+    /// [tag_set](#method.tag_set) for many checks on a server with many tags.
+    #[inline]
+    pub fn has_tag<S: AsRef<str>>(&self, tag: S) -> bool {
+        self.inner.tags.iter().any(|item| item == tag.as_ref())
+    }
+
+    /// Tags of the server as a set.
+    pub fn tag_set(&self) -> HashSet<&str> {
+        self.inner.tags.iter().map(String::as_str).collect()
+    }
+
+    transparent_property! {
+        #[doc = "Current task state (if any)."]
+        task_state: ref Option<String>
+    }
+
+    /// Whether a task (e.g. rebooting or resizing) is currently in progress.
+    #[inline]
+    pub fn is_task_in_progress(&self) -> bool {
+        self.inner.task_state.is_some()
+    }
+
     transparent_property! {
         #[doc = "Last update date and time."]
         updated_at: DateTime<FixedOffset>
     }
 
+    /// Copy the server information into a structure without a session.
+    pub fn to_data(&self) -> ServerData {
+        ServerData {
+            access_ipv4: self.inner.access_ipv4,
+            access_ipv6: self.inner.access_ipv6,
+            addresses: self.inner.addresses.clone(),
+            availability_zone: self.inner.availability_zone.clone(),
+            created_at: self.inner.created_at,
+            description: self.inner.description.clone(),
+            flavor: self.flavor.clone(),
+            has_config_drive: self.inner.has_config_drive,
+            id: self.inner.id.clone(),
+            image_id: self.image_id().cloned(),
+            instance_name: self.inner.instance_name.clone(),
+            key_pair_name: self.inner.key_pair_name.clone(),
+            metadata: self.inner.metadata.clone(),
+            name: self.inner.name.clone(),
+            power_state: self.inner.power_state,
+            progress: self.inner.progress,
+            status: self.inner.status,
+            task_state: self.inner.task_state.clone(),
+            updated_at: self.inner.updated_at,
+        }
+    }
+
     /// Delete the server.
     pub async fn delete(self) -> Result<DeletionWaiter<Server>> {
         api::delete_server(&self.session, &self.inner.id).await?;
@@ -287,36 +552,522 @@ impl Server {
         ))
     }
 
+    /// Abort an in-progress live migration of the server.
+    ///
+    /// Requires compute API version 2.24 or newer and admin privileges.
+    pub async fn abort_migration(&self, migration_id: u64) -> Result<()> {
+        api::delete_server_migration(&self.session, &self.inner.id, migration_id).await
+    }
+
+    /// Permanently delete a soft-deleted server.
+    ///
+    /// Only works on clouds with deferred deletion enabled (`reclaim_instance_interval` set),
+    /// fails otherwise.
+    pub async fn force_delete(self) -> Result<DeletionWaiter<Server>> {
+        api::server_simple_action(&self.session, &self.inner.id, "forceDelete").await?;
+        Ok(DeletionWaiter::new(
+            self,
+            Duration::new(120, 0),
+            Duration::new(1, 0),
+        ))
+    }
+
     /// Reboot the server.
+    ///
+    /// The returned waiter only finishes once the server has been seen rebooting
+    /// and is back to `ACTIVE`.
     pub async fn reboot(
         &mut self,
         reboot_type: protocol::RebootType,
     ) -> Result<ServerStatusWaiter<'_>> {
-        let mut args = HashMap::new();
-        let _ = args.insert("type", reboot_type);
-        api::server_action_with_args(&self.session, &self.inner.id, "reboot", args).await?;
+        // The cached update time may be stale, the reboot is detected by it changing.
+        self.refresh().await?;
+        let updated_at = self.inner.updated_at;
+        api::reboot_server(&self.session, &self.inner.id, reboot_type).await?;
         Ok(ServerStatusWaiter {
             server: self,
             target: protocol::ServerStatus::Active,
+            unchanged_since: Some(updated_at),
+            idle_required: true,
+        })
+    }
+
+    /// Reboot the server, wait for it to become `ACTIVE` again and return its new state.
+    pub async fn reboot_and_wait(
+        &mut self,
+        reboot_type: protocol::RebootType,
+        timeout: Duration,
+    ) -> Result<Server> {
+        self.reboot(reboot_type).await?.wait_for(timeout).await?;
+        Ok(self.clone())
+    }
+
+    /// Resize the server to a new flavor, optionally wait for the resize to finish.
+    ///
+    /// The resized server is left in the `VERIFY_RESIZE` status until the resize is confirmed
+    /// with [confirm_resize](#method.confirm_resize) or reverted with
+    /// [revert_resize](#method.revert_resize). Some clouds confirm resizes automatically after
+    /// a configured interval.
+    pub async fn resize<F: Into<FlavorRef>>(
+        &mut self,
+        flavor: F,
+    ) -> Result<ServerStatusWaiter<'_>> {
+        let flavor = flavor.into().into_verified(&self.session).await?;
+        api::resize_server(&self.session, &self.inner.id, String::from(flavor)).await?;
+        Ok(ServerStatusWaiter::new(
+            self,
+            protocol::ServerStatus::VerifyingResize,
+        ))
+    }
+
+    /// Confirm a resize, optionally wait for the server to be active.
+    ///
+    /// Note that a server that was powered off before the resize stays `SHUTOFF`, so
+    /// the returned waiter is only useful for running servers.
+    pub async fn confirm_resize(&mut self) -> Result<ServerStatusWaiter<'_>> {
+        api::server_simple_action(&self.session, &self.inner.id, "confirmResize").await?;
+        Ok(ServerStatusWaiter::new(
+            self,
+            protocol::ServerStatus::Active,
+        ))
+    }
+
+    /// Revert a resize, optionally wait for the server to be active.
+    ///
+    /// The server returns to its original flavor. As with
+    /// [confirm_resize](#method.confirm_resize), the waiter expects a running server.
+    pub async fn revert_resize(&mut self) -> Result<ServerStatusWaiter<'_>> {
+        api::server_simple_action(&self.session, &self.inner.id, "revertResize").await?;
+        Ok(ServerStatusWaiter::new(
+            self,
+            protocol::ServerStatus::Active,
+        ))
+    }
+
+    /// Resize the server, then confirm the resize if `check` passes or revert it otherwise.
+    ///
+    /// `check` is called once the server reaches `VERIFY_RESIZE`. If it returns `false`, the
+    /// resize is reverted and `OperationFailed` is returned. If the server ends up in any
+    /// other status after the resize, `OperationFailed` is returned without confirming or
+    /// reverting. Waits up to `timeout` for each step.
+    ///
+    /// The automatic revert has limits: the Compute service cannot revert a resize that has
+    /// failed (the server goes into `ERROR` instead of `VERIFY_RESIZE`) or that has already
+    /// been confirmed, so `check` is the only chance to back out.
+    pub async fn resize_with_check<F, C>(
+        &mut self,
+        flavor: F,
+        timeout: Duration,
+        check: C,
+    ) -> Result<()>
+    where
+        F: Into<FlavorRef>,
+        C: FnOnce(&Server) -> bool,
+    {
+        // Running servers become active after the resize, stopped ones stay stopped.
+        let original_status = self.inner.status;
+        self.resize(flavor).await?.wait_for(timeout).await?;
+        self.ensure_verifying_resize()?;
+
+        let (action, passed) = if check(self) {
+            ("confirmResize", true)
+        } else {
+            debug!(
+                "Resized server {} failed the check, reverting",
+                self.inner.id
+            );
+            ("revertResize", false)
+        };
+        api::server_simple_action(&self.session, &self.inner.id, action).await?;
+        ServerStatusWaiter::new(self, original_status)
+            .wait_for(timeout)
+            .await?;
+
+        if passed {
+            Ok(())
+        } else {
+            Err(Error::new(
+                ErrorKind::OperationFailed,
+                format!(
+                    "Resized server {} failed the check, the resize was reverted",
+                    self.inner.id
+                ),
+            ))
+        }
+    }
+
+    fn ensure_verifying_resize(&self) -> Result<()> {
+        if self.inner.status == protocol::ServerStatus::VerifyingResize {
+            Ok(())
+        } else {
+            Err(Error::new(
+                ErrorKind::OperationFailed,
+                format!(
+                    "Server {} is in state {} after the resize, expected VERIFY_RESIZE",
+                    self.inner.id, self.inner.status
+                ),
+            ))
+        }
+    }
+
+    /// Resize the server and confirm the resize if the server looks healthy.
+    ///
+    /// A shortcut for [resize_with_check](#method.resize_with_check) with a check that the
+    /// power state of the server has not changed (e.g. a running server is still running).
+    pub async fn resize_and_confirm<F: Into<FlavorRef>>(
+        &mut self,
+        flavor: F,
+        timeout: Duration,
+    ) -> Result<()> {
+        let check = power_state_unchanged(self.inner.power_state);
+        self.resize_with_check(flavor, timeout, check).await
+    }
+
+    /// Restore a soft-deleted server, optionally wait for it to be active.
+    ///
+    /// Only works on clouds with deferred deletion enabled (`reclaim_instance_interval` set),
+    /// fails otherwise.
+    pub async fn restore(&mut self) -> Result<ServerStatusWaiter<'_>> {
+        api::server_simple_action(&self.session, &self.inner.id, "restore").await?;
+        Ok(ServerStatusWaiter::new(
+            self,
+            protocol::ServerStatus::Active,
+        ))
+    }
+
+    /// Attach a volume to the server.
+    ///
+    /// With `delete_on_termination` set to `Some(true)` the volume is deleted together with
+    /// the server. Setting it requires compute API version 2.79 or newer and fails with
+    /// `IncompatibleApiVersion` otherwise.
+    pub async fn attach_volume<V: Into<String>>(
+        &self,
+        volume_id: V,
+        delete_on_termination: Option<bool>,
+    ) -> Result<protocol::VolumeAttachment> {
+        let request = protocol::VolumeAttachmentCreate {
+            delete_on_termination,
+            volume_id: volume_id.into(),
+        };
+        api::attach_volume(&self.session, &self.inner.id, request).await
+    }
+
+    /// Get the attachment of the volume with this ID to the server.
+    ///
+    /// Returns `None` if the volume is not attached to the server.
+    pub async fn volume_attachment<V: AsRef<str>>(
+        &self,
+        volume_id: V,
+    ) -> Result<Option<protocol::VolumeAttachment>> {
+        api::get_volume_attachment(&self.session, &self.inner.id, volume_id).await
+    }
+
+    /// Wait for a metadata key to appear on the server.
+    ///
+    /// Useful for images that report provisioning progress via metadata (for example, a
+    /// `cloud-init-done` key). The waiter returns the value of the key and fails if the server
+    /// goes into the `ERROR` state.
+    pub fn wait_for_metadata_key<K: Into<String>>(&mut self, key: K) -> ServerWaiter<'_, String> {
+        let key = key.into();
+        let description = format!("have metadata key {}", key);
+        ServerWaiter::new(self, description, move |server| {
+            server.metadata().get(&key).cloned()
+        })
+    }
+
+    /// Wait for the server to reach the given status.
+    ///
+    /// The waiter fails if the server goes into the `ERROR` state. Use `wait_for_with_delay`
+    /// to control both the timeout and the polling interval.
+    pub fn wait_for_status(&mut self, target: protocol::ServerStatus) -> ServerWaiter<'_, ()> {
+        let description = format!("reach state {}", target);
+        ServerWaiter::new(self, description, move |server| {
+            if server.status() == target {
+                Some(())
+            } else {
+                None
+            }
+        })
+    }
+
+    /// Wait for the current task of the server (if any) to finish.
+    ///
+    /// Actions are rejected with a conflict while a task is in progress, so use this waiter
+    /// before chaining actions on a server that may still be busy:
+    ///
+    /// ```rust,no_run
+    /// # async fn wrapper(server: &mut openstack::compute::Server) -> openstack::Result<()> {
+    /// use std::time::Duration;
+    /// use openstack::waiter::Waiter;
+    ///
+    /// server.wait_idle().wait_for(Duration::from_secs(300)).await?;
+    /// server.resize("m1.large").await?.wait().await?;
+    /// # Ok(()) }
+    /// ```
+    pub fn wait_idle(&mut self) -> ServerWaiter<'_, ()> {
+        ServerWaiter::new(self, "finish its current task", |server| {
+            if server.is_task_in_progress() {
+                None
+            } else {
+                Some(())
+            }
         })
     }
 
+    /// Get the console output (log) of the server.
+    ///
+    /// If `length` is provided, only that many last lines are returned. A server without any
+    /// console output and a `length` of zero both result in an empty string.
+    pub async fn console_output(&self, length: Option<u32>) -> Result<String> {
+        api::get_console_output(&self.session, &self.inner.id, length).await
+    }
+
+    /// Get a URL to connect to a remote console of the server.
+    ///
+    /// Uses the remote consoles API (compute API version 2.6 or newer) when available, falls
+    /// back to the deprecated console actions otherwise. The URL is only valid for a limited
+    /// time.
+    pub async fn remote_console(
+        &self,
+        protocol: protocol::ConsoleProtocol,
+        console_type: protocol::ConsoleType,
+    ) -> Result<protocol::RemoteConsole> {
+        api::get_remote_console(&self.session, &self.inner.id, protocol, console_type).await
+    }
+
+    /// Get a websocket URL to connect to the serial console of the server.
+    ///
+    /// The URL contains a token that expires after a short time (10 minutes by default), so it
+    /// should be requested right before connecting.
+    pub async fn serial_console(&self) -> Result<String> {
+        self.remote_console(
+            protocol::ConsoleProtocol::Serial,
+            protocol::ConsoleType::Serial,
+        )
+        .await
+        .map(|console| console.url)
+    }
+
+    /// Run an arbitrary action on the server.
+    ///
+    /// An advanced escape hatch for actions not otherwise supported by this crate: `body` is
+    /// posted to the `/servers/{id}/action` endpoint as is, and no API version is negotiated.
+    /// Returns the parsed response body, or `Null` if the response has no body.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # async fn async_wrapper() {
+    /// let os = openstack::Cloud::from_env().await.expect("Unable to authenticate");
+    /// let server = os.get_server("8a1c355b-2e1e-440a-8aa8-f272df72bc32").await
+    ///     .expect("Unable to get a server");
+    /// let output = server
+    ///     .raw_action(serde_json::json!({"os-getConsoleOutput": {"length": 50}}))
+    ///     .await
+    ///     .expect("Unable to run the action");
+    /// println!("{}", output["output"]);
+    /// # }
+    /// ```
+    pub async fn raw_action(&self, body: serde_json::Value) -> Result<serde_json::Value> {
+        api::server_raw_action(&self.session, &self.inner.id, &body).await
+    }
+
+    /// Associate a Networking API floating IP with the server.
+    ///
+    /// Updates the floating IP to point to `port_id`, or to the first port of the server if no
+    /// port is provided. Fails with `ResourceNotFound` if the server has no ports.
+    #[cfg(feature = "network")]
+    pub async fn associate_floating_ip_neutron(
+        &self,
+        floating_ip_id: &str,
+        port_id: Option<&str>,
+    ) -> Result<FloatingIp> {
+        let port: PortRef = match port_id {
+            Some(port_id) => port_id.into(),
+            None => {
+                let ports = PortQuery::new(self.session.clone())
+                    .with_device_id(self.inner.id.clone())
+                    .with_limit(1)
+                    .all()
+                    .await?;
+                first_port(&self.inner.id, ports.into_iter().map(From::from))?
+            }
+        };
+        let mut floating_ip = FloatingIp::load(self.session.clone(), floating_ip_id).await?;
+        floating_ip.associate(port, None).await?;
+        Ok(floating_ip)
+    }
+
+    /// Update the access IP addresses of the server.
+    ///
+    /// An address set to `None` is left unchanged, an empty string clears it.
+    pub async fn set_access_ip(&mut self, v4: Option<&str>, v6: Option<&str>) -> Result<()> {
+        let update = protocol::ServerUpdate {
+            access_ipv4: v4.map(From::from),
+            access_ipv6: v6.map(From::from),
+        };
+        api::update_server(&self.session, &self.inner.id, update).await?;
+        self.refresh().await
+    }
+
+    /// Delete a metadata item of the server.
+    ///
+    /// Fails with `ResourceNotFound` if the key is not set (or the server no longer exists,
+    /// the error message tells which).
+    pub async fn delete_metadata_item<K: AsRef<str>>(&mut self, key: K) -> Result<()> {
+        api::delete_server_metadata_item(&self.session, &self.inner.id, key.as_ref()).await?;
+        let _ = self.inner.metadata.remove(key.as_ref());
+        Ok(())
+    }
+
+    /// Fetch the current metadata of the server.
+    ///
+    /// Unlike `refresh`, only the metadata is updated.
+    pub async fn refresh_metadata(&mut self) -> Result<()> {
+        self.inner.metadata = api::get_server_metadata(&self.session, &self.inner.id).await?;
+        Ok(())
+    }
+
+    /// Set one metadata item of the server, replacing its value if it exists.
+    pub async fn set_metadata_item<K, V>(&mut self, key: K, value: V) -> Result<()>
+    where
+        K: Into<String>,
+        V: Into<String>,
+    {
+        let key = key.into();
+        let value = value.into();
+        api::set_server_metadata_item(&self.session, &self.inner.id, key.clone(), value.clone())
+            .await?;
+        let _ = self.inner.metadata.insert(key, value);
+        Ok(())
+    }
+
+    /// Merge the given metadata into the metadata of the server.
+    ///
+    /// Existing keys not present in `metadata` are kept.
+    pub async fn update_metadata(&mut self, metadata: HashMap<String, String>) -> Result<()> {
+        self.inner.metadata =
+            api::update_server_metadata(&self.session, &self.inner.id, metadata).await?;
+        Ok(())
+    }
+
+    /// Replace all tags of the server.
+    ///
+    /// The tags are replaced in one request, so other clients see either the old or the
+    /// new tags, never a mix of them. Requires compute API version 2.26 or newer.
+    pub async fn set_tags<I, S>(&mut self, tags: I) -> Result<()>
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let tags = tags.into_iter().map(Into::into).collect();
+        self.inner.tags = api::set_server_tags(&self.session, &self.inner.id, tags).await?;
+        Ok(())
+    }
+
     /// Start the server, optionally wait for it to be active.
     pub async fn start(&mut self) -> Result<ServerStatusWaiter<'_>> {
         api::server_simple_action(&self.session, &self.inner.id, "os-start").await?;
-        Ok(ServerStatusWaiter {
-            server: self,
-            target: protocol::ServerStatus::Active,
-        })
+        Ok(ServerStatusWaiter::new(
+            self,
+            protocol::ServerStatus::Active,
+        ))
     }
 
     /// Stop the server, optionally wait for it to be powered off.
     pub async fn stop(&mut self) -> Result<ServerStatusWaiter<'_>> {
         api::server_simple_action(&self.session, &self.inner.id, "os-stop").await?;
-        Ok(ServerStatusWaiter {
-            server: self,
-            target: protocol::ServerStatus::ShutOff,
-        })
+        Ok(ServerStatusWaiter::new(
+            self,
+            protocol::ServerStatus::ShutOff,
+        ))
+    }
+
+    /// Stop the server, wait for it to be powered off and return its new state.
+    ///
+    /// The server is refreshed first, and no stop is requested if it is already powered off.
+    pub async fn stop_and_wait(&mut self, timeout: Duration) -> Result<Server> {
+        self.refresh().await?;
+        if self.is_stopped() {
+            debug!("Server {} is already powered off", self.inner.id);
+        } else {
+            self.stop().await?.wait_for(timeout).await?;
+        }
+        Ok(self.clone())
+    }
+
+    fn is_stopped(&self) -> bool {
+        self.inner.status == protocol::ServerStatus::ShutOff && !self.is_task_in_progress()
+    }
+
+    /// Forcibly stop a misbehaving server, optionally wait for it to be powered off.
+    ///
+    /// The Compute service has no forced stop: a normal stop already powers the server off
+    /// hard if it does not shut down within the configured timeout (60 seconds by default).
+    /// A stop is, however, rejected for a server in the `ERROR` status or stuck in a task.
+    /// The server is refreshed first, and only in these cases its state is reset to `ACTIVE`
+    /// (see [reset_state](#method.reset_state)) before stopping. This fallback requires
+    /// administrator privileges, a healthy server is simply stopped.
+    pub async fn force_stop(&mut self) -> Result<ServerStatusWaiter<'_>> {
+        self.refresh().await?;
+        if self.needs_state_reset() {
+            debug!(
+                "Resetting state of server {} (status {}, task {:?}) before stopping",
+                self.inner.id, self.inner.status, self.inner.task_state
+            );
+            self.reset_state(protocol::ServerStatus::Active).await?;
+        }
+        self.stop().await
+    }
+
+    fn needs_state_reset(&self) -> bool {
+        self.inner.status == protocol::ServerStatus::Error || self.is_task_in_progress()
+    }
+
+    /// Reset the state of the server, clearing its task state.
+    ///
+    /// Only `ACTIVE` and `ERROR` are accepted, other statuses fail with `InvalidInput`.
+    /// Requires administrator privileges. The actual state of the server is not changed.
+    pub async fn reset_state(&mut self, status: protocol::ServerStatus) -> Result<()> {
+        let state = reset_state_value(status)?;
+        api::reset_server_state(&self.session, &self.inner.id, state).await?;
+        self.refresh().await
+    }
+
+    /// Pause the server, optionally wait for it to be paused.
+    pub async fn pause(&mut self) -> Result<ServerStatusWaiter<'_>> {
+        api::server_simple_action(&self.session, &self.inner.id, "pause").await?;
+        Ok(ServerStatusWaiter::new(
+            self,
+            protocol::ServerStatus::Paused,
+        ))
+    }
+
+    /// Unpause the server, optionally wait for it to be active.
+    pub async fn unpause(&mut self) -> Result<ServerStatusWaiter<'_>> {
+        api::server_simple_action(&self.session, &self.inner.id, "unpause").await?;
+        Ok(ServerStatusWaiter::new(
+            self,
+            protocol::ServerStatus::Active,
+        ))
+    }
+
+    /// Suspend the server, optionally wait for it to be suspended.
+    pub async fn suspend(&mut self) -> Result<ServerStatusWaiter<'_>> {
+        api::server_simple_action(&self.session, &self.inner.id, "suspend").await?;
+        Ok(ServerStatusWaiter::new(
+            self,
+            protocol::ServerStatus::Suspended,
+        ))
+    }
+
+    /// Resume a suspended server, optionally wait for it to be active.
+    pub async fn resume(&mut self) -> Result<ServerStatusWaiter<'_>> {
+        api::server_simple_action(&self.session, &self.inner.id, "resume").await?;
+        Ok(ServerStatusWaiter::new(
+            self,
+            protocol::ServerStatus::Active,
+        ))
     }
 }
 
@@ -335,16 +1086,44 @@ impl<'server> Waiter<(), Error> for ServerStatusWaiter<'server> {
         Error::new(
             ErrorKind::OperationTimedOut,
             format!(
-                "Timeout waiting for server {} to reach state {}",
+                "Timeout waiting for server {} to reach state {}, last seen in state {}",
                 self.server.id(),
-                self.target
+                self.target,
+                self.server.status()
             ),
         )
     }
 
     async fn poll(&mut self) -> Result<Option<()>> {
         self.server.refresh().await?;
-        if self.server.status() == self.target {
+        self.check_state()
+    }
+}
+
+impl<'server> ServerStatusWaiter<'server> {
+    fn new(server: &'server mut Server, target: protocol::ServerStatus) -> Self {
+        ServerStatusWaiter {
+            server,
+            target,
+            unchanged_since: None,
+            idle_required: false,
+        }
+    }
+
+    /// Current state of the server.
+    pub fn current_state(&self) -> &Server {
+        self.server
+    }
+
+    fn check_state(&mut self) -> Result<Option<()>> {
+        let busy = self.idle_required && self.server.is_task_in_progress();
+        if let Some(since) = self.unchanged_since {
+            if self.server.status() != self.target || busy || self.server.updated_at() != since {
+                self.unchanged_since = None;
+            }
+        }
+
+        if self.server.status() == self.target && !busy && self.unchanged_since.is_none() {
             debug!("Server {} reached state {}", self.server.id(), self.target);
             Ok(Some(()))
         } else if self.server.status() == protocol::ServerStatus::Error {
@@ -369,11 +1148,112 @@ impl<'server> Waiter<(), Error> for ServerStatusWaiter<'server> {
     }
 }
 
-impl<'server> ServerStatusWaiter<'server> {
+impl<'server, T> fmt::Debug for ServerWaiter<'server, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ServerWaiter")
+            .field("server", &self.server)
+            .field("description", &self.description)
+            .finish()
+    }
+}
+
+#[async_trait]
+impl<'server, T: Send> Waiter<T, Error> for ServerWaiter<'server, T> {
+    fn default_wait_timeout(&self) -> Option<Duration> {
+        Some(Duration::new(600, 0))
+    }
+
+    fn default_delay(&self) -> Duration {
+        Duration::new(1, 0)
+    }
+
+    fn timeout_error(&self) -> Error {
+        Error::new(
+            ErrorKind::OperationTimedOut,
+            format!(
+                "Timeout waiting for server {} to {}, last seen in state {}",
+                self.server.id(),
+                self.description,
+                self.server.status()
+            ),
+        )
+    }
+
+    async fn poll(&mut self) -> Result<Option<T>> {
+        self.server.refresh().await?;
+        self.check_server()
+    }
+}
+
+impl<'server, T> ServerWaiter<'server, T> {
+    fn new<S, F>(server: &'server mut Server, description: S, check: F) -> Self
+    where
+        S: Into<String>,
+        F: Fn(&Server) -> Option<T> + Send + Sync + 'static,
+    {
+        ServerWaiter {
+            server,
+            description: description.into(),
+            check: Box::new(check),
+        }
+    }
+
     /// Current state of the server.
     pub fn current_state(&self) -> &Server {
         self.server
     }
+
+    fn check_server(&self) -> Result<Option<T>> {
+        if let Some(value) = (self.check)(self.server) {
+            debug!(
+                "Done waiting for server {} to {}",
+                self.server.id(),
+                self.description
+            );
+            Ok(Some(value))
+        } else if self.server.status() == protocol::ServerStatus::Error {
+            debug!(
+                "Server {} got into ERROR state while waiting to {}",
+                self.server.id(),
+                self.description
+            );
+            Err(Error::new(
+                ErrorKind::OperationFailed,
+                format!("Server {} got into ERROR state", self.server.id()),
+            ))
+        } else {
+            trace!(
+                "Still waiting for server {} to {}, current state is {}",
+                self.server.id(),
+                self.description,
+                self.server.status()
+            );
+            Ok(None)
+        }
+    }
+}
+
+/// Default lifetime of console tokens in the Compute service.
+const DEFAULT_CONSOLE_TOKEN_TTL: i64 = 600;
+
+impl protocol::RemoteConsole {
+    /// Estimated expiration time of the console URL.
+    ///
+    /// The Compute service does not report when console tokens expire, so the expiry is
+    /// estimated from its default token lifetime (10 minutes since the URL was received).
+    /// Clouds with a different `[consoleauth] token_ttl` will not match the estimate.
+    pub fn expires_at(&self) -> DateTime<Utc> {
+        self.received_at + chrono::Duration::seconds(DEFAULT_CONSOLE_TOKEN_TTL)
+    }
+
+    /// Whether the console URL has expired according to `expires_at`.
+    pub fn is_expired(&self) -> bool {
+        self.is_expired_at(Utc::now())
+    }
+
+    fn is_expired_at(&self, now: DateTime<Utc>) -> bool {
+        now >= self.expires_at()
+    }
 }
 
 impl ServerSummary {
@@ -399,15 +1279,194 @@ impl ServerSummary {
     }
 }
 
+/// Maximum number of concurrent requests for operations on many servers.
+const BATCH_CONCURRENCY: usize = 10;
+
+fn reset_state_value(status: protocol::ServerStatus) -> Result<&'static str> {
+    match status {
+        protocol::ServerStatus::Active => Ok("active"),
+        protocol::ServerStatus::Error => Ok("error"),
+        other => Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!(
+                "Server state can only be reset to ACTIVE or ERROR, not {}",
+                other
+            ),
+        )),
+    }
+}
+
+fn reverse_raw_servers(body: &mut serde_json::Value) {
+    if let Some(servers) = body
+        .get_mut("servers")
+        .and_then(serde_json::Value::as_array_mut)
+    {
+        servers.reverse();
+    }
+}
+
+#[cfg(feature = "network")]
+fn first_port<I: IntoIterator<Item = PortRef>>(server_id: &str, ports: I) -> Result<PortRef> {
+    ports.into_iter().next().ok_or_else(|| {
+        Error::new(
+            ErrorKind::ResourceNotFound,
+            format!("Server {} has no ports", server_id),
+        )
+    })
+}
+
+fn power_state_unchanged(before: protocol::ServerPowerState) -> impl Fn(&Server) -> bool {
+    move |server| server.power_state() == before
+}
+
+/// Merge the given metadata into the metadata of each server.
+///
+/// Runs concurrently and returns the result for each server ID in the order of completion.
+pub(crate) async fn update_metadata_many<I>(
+    session: &Session,
+    ids: I,
+    metadata: HashMap<String, String>,
+) -> Vec<(String, Result<()>)>
+where
+    I: IntoIterator,
+    I::Item: Into<String>,
+{
+    for_each_server(ids, |id| {
+        let metadata = metadata.clone();
+        async move {
+            api::update_server_metadata(session, &id, metadata)
+                .await
+                .map(|_| ())
+        }
+    })
+    .await
+}
+
+/// Run an operation concurrently for each server ID, collecting every result.
+async fn for_each_server<I, F, Fut>(ids: I, op: F) -> Vec<(String, Result<()>)>
+where
+    I: IntoIterator,
+    I::Item: Into<String>,
+    F: Fn(String) -> Fut,
+    Fut: Future<Output = Result<()>>,
+{
+    stream::iter(ids.into_iter().map(Into::into))
+        .map(|id: String| {
+            let result = op(id.clone());
+            async move { (id, result.await) }
+        })
+        .buffer_unordered(BATCH_CONCURRENCY)
+        .collect()
+        .await
+}
+
+/// Sum the flavor resources of the given servers.
+///
+/// Each distinct flavor is only fetched once.
+pub(crate) async fn aggregate_usage<I>(session: &Session, ids: I) -> Result<ResourceTotals>
+where
+    I: IntoIterator,
+    I::Item: AsRef<str>,
+{
+    let ids: Vec<_> = ids.into_iter().collect();
+    let servers: Vec<protocol::Server> = stream::iter(ids.iter())
+        .map(|id| api::get_server_by_id(session, id))
+        .buffer_unordered(BATCH_CONCURRENCY)
+        .try_collect()
+        .await?;
+    let flavors = fetch_flavors(&servers, |id| api::get_flavor_by_id(session, id)).await?;
+    sum_usage(&servers, &flavors)
+}
+
+/// Fetch each distinct flavor of the given servers once.
+async fn fetch_flavors<'s, F, Fut>(
+    servers: &'s [protocol::Server],
+    fetch: F,
+) -> Result<HashMap<String, protocol::Flavor>>
+where
+    F: Fn(&'s str) -> Fut,
+    Fut: Future<Output = Result<protocol::Flavor>>,
+{
+    stream::iter(flavor_ids(servers))
+        .map(|id| {
+            let flavor = fetch(id);
+            async move { flavor.await.map(|flavor| (id.to_string(), flavor)) }
+        })
+        .buffer_unordered(BATCH_CONCURRENCY)
+        .try_collect()
+        .await
+}
+
+fn flavor_ids(servers: &[protocol::Server]) -> HashSet<&str> {
+    servers.iter().map(|s| s.flavor.id.as_str()).collect()
+}
+
+fn sum_usage(
+    servers: &[protocol::Server],
+    flavors: &HashMap<String, protocol::Flavor>,
+) -> Result<ResourceTotals> {
+    let mut totals = ResourceTotals::default();
+    for server in servers {
+        let flavor = flavors.get(&server.flavor.id).ok_or_else(|| {
+            Error::new(
+                ErrorKind::ResourceNotFound,
+                format!(
+                    "Flavor {} of server {} was not found",
+                    server.flavor.id, server.id
+                ),
+            )
+        })?;
+        totals.ram_size += flavor.ram;
+        totals.root_size += flavor.disk;
+        totals.vcpu_count += u64::from(flavor.vcpus);
+    }
+    Ok(totals)
+}
+
 impl ServerQuery {
     pub(crate) fn new(session: Session) -> ServerQuery {
         ServerQuery {
             session,
             query: Query::new(),
             can_paginate: true,
+            api_version: None,
+            reverse: false,
+            page_size: None,
         }
     }
 
+    fn build_query(&self, limit: Option<usize>, marker: Option<String>) -> Query {
+        let mut query = self.query.with_marker_and_limit(limit, marker);
+        if self.reverse {
+            let mut has_sort = false;
+            for (key, value) in query.0.iter_mut() {
+                if key == "sort_dir" {
+                    has_sort = true;
+                    *value = if value == "desc" { "asc" } else { "desc" }.into();
+                }
+            }
+            if !has_sort {
+                // Reverse of the default ordering.
+                query.push_str("sort_key", "created_at");
+                query.push_str("sort_dir", "asc");
+                query.push_str("sort_key", "id");
+                query.push_str("sort_dir", "asc");
+            }
+        }
+        query
+    }
+
+    fn with_tags_filter<I>(mut self, name: &str, tags: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        let tags: Vec<String> = tags.into_iter().map(Into::into).collect();
+        self.query.push_str(name, tags.join(","));
+        self.api_version = Some(api::API_VERSION_SERVER_TAGS);
+        self
+    }
+
     /// Add marker to the request.
     ///
     /// Using this disables automatic pagination.
@@ -417,6 +1476,18 @@ impl ServerQuery {
         self
     }
 
+    /// Add marker to the request to fetch the servers preceding it.
+    ///
+    /// This is used to page backwards: the sorting directions are flipped for the request
+    /// and the results are reversed back, so they come in the same order as with
+    /// `with_marker`. Without explicit sorting, the default order (newest first) is assumed.
+    ///
+    /// Using this disables automatic pagination.
+    pub fn with_marker_before<T: Into<String>>(mut self, marker: T) -> Self {
+        self.reverse = true;
+        self.with_marker(marker)
+    }
+
     /// Add limit to the request.
     ///
     /// Using this disables automatic pagination.
@@ -426,6 +1497,15 @@ impl ServerQuery {
         self
     }
 
+    /// Set the number of servers to request per page.
+    ///
+    /// Unlike `with_limit`, automatic pagination is kept and all matching servers are
+    /// returned. The Compute service may still return shorter pages.
+    pub fn with_page_size(mut self, page_size: usize) -> Self {
+        self.page_size = Some(page_size);
+        self
+    }
+
     /// Add sorting to the request.
     pub fn sort_by(mut self, sort: Sort<protocol::ServerSortKey>) -> Self {
         let (field, direction) = sort.into();
@@ -444,11 +1524,37 @@ impl ServerQuery {
         set_access_ip_v6, with_access_ip_v6 -> access_ip_v6: Ipv6Addr
     }
 
+    /// List servers of all projects (only allowed for admins).
+    ///
+    /// With `enabled` set to `false`, the filter is removed from the query, so the results
+    /// are limited to the current project.
+    pub fn set_all_tenants(&mut self, enabled: bool) {
+        self.query.remove("all_tenants");
+        if enabled {
+            self.query.push("all_tenants", 1);
+        }
+    }
+
+    /// List servers of all projects (only allowed for admins).
+    ///
+    /// With `enabled` set to `false`, the filter is removed from the query, so the results
+    /// are limited to the current project.
+    #[inline]
+    pub fn with_all_tenants(mut self, enabled: bool) -> Self {
+        self.set_all_tenants(enabled);
+        self
+    }
+
     query_filter! {
         #[doc = "Filter by availability zone."]
         set_availability_zone, with_availability_zone -> availability_zone: String
     }
 
+    query_filter! {
+        #[doc = "Filter by deletion state (only allowed for admins)."]
+        set_deleted, with_deleted -> deleted: bool
+    }
+
     query_filter! {
         #[doc = "Filter by flavor."]
         set_flavor, with_flavor -> flavor: FlavorRef
@@ -471,11 +1577,18 @@ impl ServerQuery {
 
     query_filter! {
         #[doc = "Filter by an IPv6 address."]
+        #[doc = ""]
+        #[doc = "Maps to the `ip6` parameter of the Compute API. The address is matched as a"]
+        #[doc = "regular expression against the addresses known to the Compute service, which"]
+        #[doc = "normally include fixed addresses; whether floating addresses are matched"]
+        #[doc = "depends on the cloud. Use `detailed` to get full server objects."]
         set_ip_v6, with_ip_v6 -> ip6: Ipv6Addr
     }
 
     query_filter! {
         #[doc = "Filter by name."]
+        #[doc = ""]
+        #[doc = "The Compute service treats the name as a regular expression."]
         set_name, with_name -> name: String
     }
 
@@ -484,6 +1597,11 @@ impl ServerQuery {
         set_project, with_project -> project_id: ProjectRef
     }
 
+    query_filter! {
+        #[doc = "Filter by the reservation ID returned when creating several servers."]
+        set_reservation_id, with_reservation_id -> reservation_id: String
+    }
+
     query_filter! {
         #[doc = "Filter by server status."]
         set_status, with_status -> status: protocol::ServerStatus
@@ -494,6 +1612,50 @@ impl ServerQuery {
         set_user, with_user -> user_id: UserRef
     }
 
+    /// Filter by tags: only servers having all of the given tags are returned.
+    ///
+    /// Requires compute API version 2.26 or newer.
+    pub fn with_tags_all<I>(self, tags: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        self.with_tags_filter("tags", tags)
+    }
+
+    /// Filter by tags: only servers having at least one of the given tags are returned.
+    ///
+    /// Requires compute API version 2.26 or newer.
+    pub fn with_tags_any<I>(self, tags: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        self.with_tags_filter("tags-any", tags)
+    }
+
+    /// Filter by tags: servers having all of the given tags are excluded.
+    ///
+    /// Requires compute API version 2.26 or newer.
+    pub fn without_tags<I>(self, tags: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        self.with_tags_filter("not-tags", tags)
+    }
+
+    /// Filter by tags: servers having at least one of the given tags are excluded.
+    ///
+    /// Requires compute API version 2.26 or newer.
+    pub fn without_tags_any<I>(self, tags: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        self.with_tags_filter("not-tags-any", tags)
+    }
+
     /// Convert this query into a detailed query.
     ///
     /// Detailed queries return full `Server` objects instead of just `ServerSummary`.
@@ -525,6 +1687,20 @@ impl ServerQuery {
         self.into_stream().try_collect().await
     }
 
+    /// Fetch one page of at most `limit` results starting after `marker`.
+    ///
+    /// Unlike `all`, the returned page carries the marker of the next page (if any).
+    /// Must not be combined with `with_limit` or `with_marker`. Fails with `InvalidInput`
+    /// if `limit` is zero.
+    pub async fn fetch_page(
+        self,
+        limit: usize,
+        marker: Option<String>,
+    ) -> Result<Page<ServerSummary>> {
+        debug!("Fetching a page of servers with {:?}", self.query);
+        ResourceIterator::new(self).fetch_page(limit, marker).await
+    }
+
     /// Return one and exactly one result.
     ///
     /// Fails with `ResourceNotFound` if the query produces no results and
@@ -551,6 +1727,10 @@ impl ResourceQuery for ServerQuery {
         Ok(self.can_paginate)
     }
 
+    fn page_size(&self) -> usize {
+        self.page_size.unwrap_or(Self::DEFAULT_LIMIT)
+    }
+
     fn extract_marker(&self, resource: &Self::Item) -> String {
         resource.id().clone()
     }
@@ -560,15 +1740,32 @@ impl ResourceQuery for ServerQuery {
         limit: Option<usize>,
         marker: Option<String>,
     ) -> Result<Vec<Self::Item>> {
-        let query = self.query.with_marker_and_limit(limit, marker);
-        Ok(api::list_servers(&self.session, &query)
+        let query = self.build_query(limit, marker);
+        let mut result: Vec<_> = api::list_servers(&self.session, &query, self.api_version)
             .await?
             .into_iter()
             .map(|srv| ServerSummary {
                 session: self.session.clone(),
                 inner: srv,
             })
-            .collect())
+            .collect();
+        if self.reverse {
+            result.reverse();
+        }
+        Ok(result)
+    }
+
+    async fn validate(&mut self) -> Result<()> {
+        if self.page_size == Some(0) {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Page size must be positive",
+            ));
+        }
+        if let Some(version) = self.api_version {
+            api::ensure_api_version(&self.session, version, "This server query").await?;
+        }
+        Ok(())
     }
 }
 
@@ -585,6 +1782,92 @@ impl DetailedServerQuery {
         debug!("Fetching server details with {:?}", self.inner.query);
         ResourceIterator::new(self).into_stream()
     }
+
+    /// Execute one request and return its results together with the raw response body.
+    ///
+    /// Intended for debugging differences between the cloud's responses and this crate.
+    /// No automatic pagination happens: only the first page (or the page selected by
+    /// `with_limit` and `with_marker`) is returned. For reversed queries the servers in the
+    /// raw body are reversed as well, so that they come in the same order as the results.
+    pub async fn fetch_raw(mut self) -> Result<(Vec<Server>, serde_json::Value)> {
+        debug!("Fetching raw server details with {:?}", self.inner.query);
+        self.inner.validate().await?;
+        let query = self.inner.build_query(None, None);
+        let (servers, mut body) =
+            api::list_servers_detail_raw(&self.inner.session, &query, self.inner.api_version)
+                .await?;
+        if self.inner.reverse {
+            reverse_raw_servers(&mut body);
+        }
+        Ok((self.convert_servers(servers).await?, body))
+    }
+
+    async fn convert_servers(&self, servers: Vec<protocol::Server>) -> Result<Vec<Server>> {
+        let mut result = Vec::with_capacity(servers.len());
+        for srv in servers {
+            result.push(Server::new(self.inner.session.clone(), srv).await?);
+        }
+        if self.inner.reverse {
+            result.reverse();
+        }
+        Ok(result)
+    }
+
+    /// Fetch one page of at most `limit` results starting after `marker`.
+    ///
+    /// Unlike `into_stream`, the returned page carries the marker of the next page (if any).
+    /// Must not be combined with `with_limit` or `with_marker`. Fails with `InvalidInput`
+    /// if `limit` is zero.
+    pub async fn fetch_page(self, limit: usize, marker: Option<String>) -> Result<Page<Server>> {
+        debug!(
+            "Fetching a page of server details with {:?}",
+            self.inner.query
+        );
+        ResourceIterator::new(self).fetch_page(limit, marker).await
+    }
+
+    /// Write the servers to `writer` as newline-delimited JSON.
+    ///
+    /// Each server is written on its own line in the form of `ServerData`, and the writer is
+    /// flushed after each page. Servers are fetched page by page, so the whole list is never
+    /// kept in memory.
+    pub async fn write_ndjson<W: Write>(self, writer: W) -> Result<()> {
+        debug!("Writing server details with {:?}", self.inner.query);
+        write_ndjson_pages(ResourceIterator::new(self), writer).await
+    }
+}
+
+async fn write_ndjson_pages<Q, W>(servers: ResourceIterator<Q>, mut writer: W) -> Result<()>
+where
+    Q: ResourceQuery<Item = Server> + Send,
+    W: Write,
+{
+    let pages = servers.into_page_stream();
+    futures::pin_mut!(pages);
+    while let Some(page) = pages.try_next().await? {
+        for server in &page {
+            write_ndjson_line(&mut writer, &server.to_data())?;
+        }
+        writer.flush().map_err(|err| {
+            Error::new(
+                ErrorKind::OperationFailed,
+                format!("Cannot flush servers: {}", err),
+            )
+        })?;
+    }
+    Ok(())
+}
+
+fn write_ndjson_line<W: Write>(writer: &mut W, data: &ServerData) -> Result<()> {
+    serde_json::to_writer(&mut *writer, data)
+        .map_err(io::Error::from)
+        .and_then(|_| writer.write_all(b"\n"))
+        .map_err(|err| {
+            Error::new(
+                ErrorKind::OperationFailed,
+                format!("Cannot write server {}: {}", data.id, err),
+            )
+        })
 }
 
 #[async_trait]
@@ -597,6 +1880,10 @@ impl ResourceQuery for DetailedServerQuery {
         Ok(self.inner.can_paginate)
     }
 
+    fn page_size(&self) -> usize {
+        self.inner.page_size.unwrap_or(Self::DEFAULT_LIMIT)
+    }
+
     fn extract_marker(&self, resource: &Self::Item) -> String {
         resource.id().clone()
     }
@@ -606,13 +1893,14 @@ impl ResourceQuery for DetailedServerQuery {
         limit: Option<usize>,
         marker: Option<String>,
     ) -> Result<Vec<Self::Item>> {
-        let query = self.inner.query.with_marker_and_limit(limit, marker);
-        let servers = api::list_servers_detail(&self.inner.session, &query).await?;
-        let mut result = Vec::with_capacity(servers.len());
-        for srv in servers {
-            result.push(Server::new(self.inner.session.clone(), srv).await?);
-        }
-        Ok(result)
+        let query = self.inner.build_query(limit, marker);
+        let servers =
+            api::list_servers_detail(&self.inner.session, &query, self.inner.api_version).await?;
+        self.convert_servers(servers).await
+    }
+
+    async fn validate(&mut self) -> Result<()> {
+        self.inner.validate().await
     }
 }
 
@@ -633,15 +1921,28 @@ async fn convert_networks(
     networks: Vec<ServerNIC>,
 ) -> Result<Vec<protocol::ServerNetwork>> {
     let mut result = Vec::with_capacity(networks.len());
+    // Compared after verification, so that a port given by name and by ID is caught.
+    let mut ports = HashSet::new();
     for item in networks {
         result.push(match item {
             ServerNIC::FromNetwork(n) => protocol::ServerNetwork::Network {
                 uuid: n.into_verified(session).await?.into(),
             },
-            ServerNIC::WithPort(p) => protocol::ServerNetwork::Port {
-                port: p.into_verified(session).await?.into(),
-            },
+            ServerNIC::WithPort(p) => {
+                let port: String = p.into_verified(session).await?.into();
+                if !ports.insert(port.clone()) {
+                    return Err(Error::new(
+                        ErrorKind::InvalidInput,
+                        format!("Port {} is used for more than one NIC", port),
+                    ));
+                }
+                protocol::ServerNetwork::Port { port }
+            }
             ServerNIC::WithFixedIp(ip) => protocol::ServerNetwork::FixedIp { fixed_ip: ip },
+            ServerNIC::FromNetworkWithFixedIp(n, ip) => protocol::ServerNetwork::NetworkFixedIp {
+                uuid: n.into_verified(session).await?.into(),
+                fixed_ip: ip,
+            },
         });
     }
     Ok(result)
@@ -659,22 +1960,128 @@ impl NewServer {
             name,
             nics: Vec::new(),
             block_devices: Vec::new(),
+            security_groups: None,
+            trusted_image_certificates: Vec::new(),
             user_data: None,
             config_drive: None,
             availability_zone: None,
+            host: None,
+            hypervisor_hostname: None,
+        }
+    }
+
+    /// Check that the request has everything required to create a server.
+    ///
+    /// Fails with `InvalidInput` naming the missing field or if the same port is used for
+    /// several NICs. This is also done by `create`.
+    pub fn validate(&self) -> Result<()> {
+        if self.name.is_empty() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "A name is required to create a server",
+            ));
+        }
+        if self.flavor.as_ref().is_empty() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "A flavor is required to create a server",
+            ));
+        }
+        if self.image.is_none() && self.block_devices.is_empty() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Either an image or a block device is required to create a server",
+            ));
+        }
+        if let Some(ref groups) = self.security_groups {
+            if groups.is_empty() {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    "The list of security groups must not be empty, leave it unset \
+                     to use the default security group",
+                ));
+            }
+            if groups.iter().any(|group| group.as_ref().is_empty()) {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    "Security group names must not be empty",
+                ));
+            }
         }
+        Ok(())
     }
 
     /// Request creation of the server.
     pub async fn create(self) -> Result<ServerCreationWaiter> {
+        let (session, request) = self.into_request().await?;
+        let server_ref = api::create_server(&session, request).await?;
+        Ok(ServerCreationWaiter {
+            server: Server::load(session, server_ref.id).await?,
+        })
+    }
+
+    /// Request creation of `count` identical servers.
+    ///
+    /// All servers share the same reservation ID. The returned waiter lists them by it and
+    /// fails as soon as any of them goes into `ERROR`. On failure or timeout the waiter returns
+    /// a [ServerBatchError] with the servers seen so far, so that they can be cleaned up.
+    ///
+    /// ```rust,no_run
+    /// # async fn create() -> openstack::Result<()> {
+    /// use std::time::Duration;
+    ///
+    /// use openstack::waiter::Waiter;
+    ///
+    /// let os = openstack::Cloud::from_env().await?;
+    /// let servers = os
+    ///     .new_server("worker", "m1.small")
+    ///     .with_image("cirros")
+    ///     .create_many(3)
+    ///     .await?
+    ///     .wait_for(Duration::from_secs(600))
+    ///     .await?;
+    /// assert_eq!(servers.len(), 3);
+    /// # Ok(()) }
+    /// ```
+    pub async fn create_many(self, count: u32) -> Result<ServerBatchWaiter> {
+        if count == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "At least one server must be requested",
+            ));
+        }
+
+        let (session, request) = self.into_request().await?;
+        let reservation_id = api::create_servers(&session, request, count).await?;
+        Ok(ServerBatchWaiter {
+            session,
+            reservation_id,
+            count: count as usize,
+            servers: Vec::new(),
+        })
+    }
+
+    async fn into_request(self) -> Result<(Session, protocol::ServerCreate)> {
+        self.validate()?;
+
         let mut block_devices = Vec::with_capacity(self.block_devices.len());
         for bd in self.block_devices {
             block_devices.push(bd.into_verified(&self.session).await?);
         }
 
+        // Nova resolves security group names (and IDs) itself.
+        let security_groups = self
+            .security_groups
+            .unwrap_or_default()
+            .into_iter()
+            .map(|group| protocol::ServerSecurityGroup { name: group.into() })
+            .collect();
+
         let request = protocol::ServerCreate {
             block_devices,
             flavorRef: self.flavor.into_verified(&self.session).await?.into(),
+            host: self.host,
+            hypervisor_hostname: self.hypervisor_hostname,
             imageRef: match self.image {
                 Some(img) => Some(img.into_verified(&self.session).await?.into()),
                 None => None,
@@ -683,18 +2090,20 @@ impl NewServer {
                 Some(item) => Some(item.into_verified(&self.session).await?.into()),
                 None => None,
             },
+            max_count: None,
             metadata: self.metadata,
+            min_count: None,
             name: self.name,
             networks: convert_networks(&self.session, self.nics).await?,
+            return_reservation_id: None,
+            security_groups,
+            trusted_image_certificates: self.trusted_image_certificates,
             user_data: self.user_data,
             config_drive: self.config_drive,
             availability_zone: self.availability_zone,
         };
 
-        let server_ref = api::create_server(&self.session, request).await?;
-        Ok(ServerCreationWaiter {
-            server: Server::load(self.session, server_ref.id).await?,
-        })
+        Ok((self.session, request))
     }
 
     /// Add a virtual NIC with given fixed IP to the new server.
@@ -712,6 +2121,16 @@ impl NewServer {
         self.nics.push(ServerNIC::FromNetwork(network.into()));
     }
 
+    /// Add a virtual NIC from this network with the given fixed IP to the new server.
+    #[inline]
+    pub fn add_network_with_fixed_ip<N>(&mut self, network: N, fixed_ip: IpAddr)
+    where
+        N: Into<NetworkRef>,
+    {
+        self.nics
+            .push(ServerNIC::FromNetworkWithFixedIp(network.into(), fixed_ip));
+    }
+
     /// Add a virtual NIC with this port to the new server.
     #[inline]
     pub fn add_port<P>(&mut self, port: P)
@@ -721,6 +2140,14 @@ impl NewServer {
         self.nics.push(ServerNIC::WithPort(port.into()));
     }
 
+    /// Add a security group to the new server.
+    ///
+    /// The `default` security group is used if none are added.
+    #[inline]
+    pub fn add_security_group<S: Into<SecurityGroupRef>>(&mut self, group: S) {
+        self.security_groups().push(group.into());
+    }
+
     /// Metadata assigned to this server.
     #[inline]
     pub fn metadata(&mut self) -> &mut HashMap<String, String> {
@@ -739,6 +2166,15 @@ impl NewServer {
         &mut self.block_devices
     }
 
+    /// Security groups of this server.
+    ///
+    /// Calling this method marks the security groups as explicitly set: creation fails if
+    /// the list is left empty.
+    #[inline]
+    pub fn security_groups(&mut self) -> &mut Vec<SecurityGroupRef> {
+        self.security_groups.get_or_insert_with(Vec::new)
+    }
+
     /// Use this image as a source for the new server.
     pub fn set_image<I>(&mut self, image: I)
     where
@@ -756,6 +2192,9 @@ impl NewServer {
     }
 
     /// Use this availability_zone for the new server.
+    ///
+    /// The value is passed to the Compute API unmodified, so the `zone:host:node` forced
+    /// placement syntax can be used as well (see `set_forced_host`).
     pub fn set_availability_zone<A>(&mut self, availability_zone: A)
     where
         A: Into<String>,
@@ -763,6 +2202,44 @@ impl NewServer {
         self.availability_zone = Some(availability_zone.into());
     }
 
+    /// Force the new server onto the given host (and optionally node) in the availability zone.
+    ///
+    /// Builds the `zone:host:node` form of the availability zone, bypassing the scheduler
+    /// filters. Requires the admin role (by default policy).
+    pub fn set_forced_host<A, H>(&mut self, availability_zone: A, host: H, node: Option<&str>)
+    where
+        A: AsRef<str>,
+        H: AsRef<str>,
+    {
+        let mut value = format!("{}:{}", availability_zone.as_ref(), host.as_ref());
+        if let Some(node) = node {
+            value.push(':');
+            value.push_str(node);
+        }
+        self.availability_zone = Some(value);
+    }
+
+    /// Request the new server to be created on the given compute host.
+    ///
+    /// Unlike `set_forced_host`, the scheduler still validates the host. Requires compute API
+    /// version 2.74 or newer and the admin role (by default policy).
+    pub fn set_host<H>(&mut self, host: H)
+    where
+        H: Into<String>,
+    {
+        self.host = Some(host.into());
+    }
+
+    /// Request the new server to be created on the given hypervisor node.
+    ///
+    /// Requires compute API version 2.74 or newer and the admin role (by default policy).
+    pub fn set_hypervisor_hostname<H>(&mut self, hypervisor_hostname: H)
+    where
+        H: Into<String>,
+    {
+        self.hypervisor_hostname = Some(hypervisor_hostname.into());
+    }
+
     /// Add a block device to attach to the server.
     #[inline]
     pub fn with_block_device(mut self, block_device: BlockDevice) -> Self {
@@ -816,6 +2293,48 @@ impl NewServer {
         self
     }
 
+    /// Force the new server onto the given host (and optionally node) in the availability zone.
+    ///
+    /// Requires the admin role (by default policy).
+    #[inline]
+    pub fn with_forced_host<A, H>(
+        mut self,
+        availability_zone: A,
+        host: H,
+        node: Option<&str>,
+    ) -> NewServer
+    where
+        A: AsRef<str>,
+        H: AsRef<str>,
+    {
+        self.set_forced_host(availability_zone, host, node);
+        self
+    }
+
+    /// Request the new server to be created on the given compute host.
+    ///
+    /// Requires compute API version 2.74 or newer and the admin role (by default policy).
+    #[inline]
+    pub fn with_host<H>(mut self, host: H) -> NewServer
+    where
+        H: Into<String>,
+    {
+        self.set_host(host);
+        self
+    }
+
+    /// Request the new server to be created on the given hypervisor node.
+    ///
+    /// Requires compute API version 2.74 or newer and the admin role (by default policy).
+    #[inline]
+    pub fn with_hypervisor_hostname<H>(mut self, hypervisor_hostname: H) -> NewServer
+    where
+        H: Into<String>,
+    {
+        self.set_hypervisor_hostname(hypervisor_hostname);
+        self
+    }
+
     /// Add an arbitrary key/value metadata pair.
     pub fn with_metadata<S1, S2>(mut self, key: S1, value: S2) -> NewServer
     where
@@ -836,6 +2355,16 @@ impl NewServer {
         self
     }
 
+    /// Add a virtual NIC from this network with the given fixed IP to the new server.
+    #[inline]
+    pub fn with_network_with_fixed_ip<N>(mut self, network: N, fixed_ip: IpAddr) -> NewServer
+    where
+        N: Into<NetworkRef>,
+    {
+        self.add_network_with_fixed_ip(network, fixed_ip);
+        self
+    }
+
     /// Create a volume to boot from from an image.
     #[inline]
     pub fn with_new_boot_volume<I>(self, image: I, size_gib: u32) -> Self
@@ -855,11 +2384,38 @@ impl NewServer {
         self
     }
 
+    /// Add a security group to the new server.
+    #[inline]
+    pub fn with_security_group<S: Into<SecurityGroupRef>>(mut self, group: S) -> NewServer {
+        self.add_security_group(group);
+        self
+    }
+
+    /// Add several security groups to the new server.
+    ///
+    /// An empty list is rejected on creation.
+    pub fn with_security_groups<I, S>(mut self, groups: I) -> NewServer
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<SecurityGroupRef>,
+    {
+        self.security_groups()
+            .extend(groups.into_iter().map(Into::into));
+        self
+    }
+
     creation_field! {
         #[doc = "Use this user-data for the new server."]
         set_user_data, with_user_data -> user_data: optional String
     }
 
+    creation_field! {
+        #[doc = "Validate the image signature against these trusted certificates."]
+        #[doc = ""]
+        #[doc = "Requires compute API version 2.63 or newer."]
+        set_trusted_image_certificates, with_trusted_image_certificates -> trusted_image_certificates: Vec<String>
+    }
+
     creation_field! {
         #[doc = "Enable/disable config-drive for the new server."]
         set_config_drive, with_config_drive -> config_drive: optional bool
@@ -918,3 +2474,1162 @@ impl ServerCreationWaiter {
         &self.server
     }
 }
+
+impl ServerBatchError {
+    /// The underlying error.
+    pub fn error(&self) -> &Error {
+        &self.error
+    }
+
+    /// Servers seen before the failure.
+    pub fn servers(&self) -> &[Server] {
+        &self.servers
+    }
+
+    /// Split into the servers seen before the failure and the underlying error.
+    pub fn into_parts(self) -> (Vec<Server>, Error) {
+        (self.servers, self.error)
+    }
+}
+
+impl fmt::Display for ServerBatchError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&self.error, f)
+    }
+}
+
+impl std::error::Error for ServerBatchError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+impl From<ServerBatchError> for Error {
+    fn from(value: ServerBatchError) -> Error {
+        value.error
+    }
+}
+
+#[async_trait]
+impl Waiter<Vec<Server>, ServerBatchError> for ServerBatchWaiter {
+    fn default_wait_timeout(&self) -> Option<Duration> {
+        Some(Duration::new(1800, 0))
+    }
+
+    fn default_delay(&self) -> Duration {
+        Duration::new(5, 0)
+    }
+
+    fn timeout_error(&self) -> ServerBatchError {
+        let statuses: Vec<_> = self
+            .servers
+            .iter()
+            .map(|srv| format!("{} is {}", srv.id(), srv.status()))
+            .collect();
+        self.batch_error(Error::new(
+            ErrorKind::OperationTimedOut,
+            format!(
+                "Timeout waiting for {} servers of reservation {} to become ACTIVE, \
+                 seen {}: [{}]",
+                self.count,
+                self.reservation_id,
+                self.servers.len(),
+                statuses.join(", ")
+            ),
+        ))
+    }
+
+    async fn poll(&mut self) -> std::result::Result<Option<Vec<Server>>, ServerBatchError> {
+        let listed = ServerQuery::new(self.session.clone())
+            .with_reservation_id(self.reservation_id.clone())
+            .detailed()
+            .into_stream()
+            .try_collect()
+            .await;
+        match listed {
+            Ok(servers) => self.servers = servers,
+            Err(err) => return Err(self.batch_error(err)),
+        }
+        self.check_servers().map_err(|err| self.batch_error(err))
+    }
+}
+
+impl ServerBatchWaiter {
+    /// Current state of the waiter: the servers seen so far.
+    pub fn current_state(&self) -> &[Server] {
+        &self.servers
+    }
+
+    /// Reservation ID shared by the new servers.
+    pub fn reservation_id(&self) -> &String {
+        &self.reservation_id
+    }
+
+    fn batch_error(&self, error: Error) -> ServerBatchError {
+        ServerBatchError {
+            servers: self.servers.clone(),
+            error,
+        }
+    }
+
+    fn check_servers(&self) -> Result<Option<Vec<Server>>> {
+        let failed: Vec<_> = self
+            .servers
+            .iter()
+            .filter(|srv| srv.status() == protocol::ServerStatus::Error)
+            .map(|srv| srv.id().as_str())
+            .collect();
+        if !failed.is_empty() {
+            debug!(
+                "Failed to create servers of reservation {} - {} are in ERROR",
+                self.reservation_id,
+                failed.join(", ")
+            );
+            return Err(Error::new(
+                ErrorKind::OperationFailed,
+                format!(
+                    "Servers {} of reservation {} got into ERROR state",
+                    failed.join(", "),
+                    self.reservation_id
+                ),
+            ));
+        }
+
+        let active = self
+            .servers
+            .iter()
+            .filter(|srv| srv.status() == protocol::ServerStatus::Active)
+            .count();
+        if active >= self.count {
+            debug!(
+                "All {} servers of reservation {} successfully created",
+                self.count, self.reservation_id
+            );
+            Ok(Some(self.servers.clone()))
+        } else {
+            trace!(
+                "Still waiting for servers of reservation {}, {} of {} are ACTIVE",
+                self.reservation_id,
+                active,
+                self.count
+            );
+            Ok(None)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+    use std::io::{self, Write};
+
+    use async_trait::async_trait;
+    use chrono::TimeZone;
+    use osauth::NoAuth;
+
+    use super::super::super::common::{
+        ApiVersion, NetworkRef, PortRef, ResourceIterator, ResourceQuery,
+    };
+    use super::super::super::session::Session;
+    use super::super::super::utils::Query;
+    use super::super::super::waiter::Waiter;
+    use super::super::super::{Error, ErrorKind, Result, Sort};
+    use super::super::{protocol, BlockDevice};
+    use super::{
+        convert_networks, fetch_flavors, flavor_ids, for_each_server, power_state_unchanged,
+        reset_state_value, reverse_raw_servers, sum_usage, write_ndjson_line, write_ndjson_pages,
+        NewServer, ResourceTotals, Server, ServerBatchWaiter, ServerNIC, ServerQuery,
+        ServerStatusWaiter,
+    };
+
+    async fn new_session() -> Session {
+        // Nothing listens on this port, so requests fail to connect instead of reaching a
+        // local cloud.
+        let auth = NoAuth::new("http://127.0.0.1:1/compute").unwrap();
+        Session::new(auth).await.unwrap()
+    }
+
+    async fn new_query() -> ServerQuery {
+        ServerQuery::new(new_session().await)
+    }
+
+    fn server_json(status: &str, task_state: Option<&str>, updated: &str) -> serde_json::Value {
+        serde_json::json!({
+            "OS-EXT-AZ:availability_zone": "nova",
+            "OS-EXT-STS:task_state": task_state,
+            "config_drive": "",
+            "created": "2020-01-01T00:00:00Z",
+            "flavor": {"id": "1", "links": []},
+            "id": "abcd",
+            "name": "test",
+            "status": status,
+            "tenant_id": "demo",
+            "updated": updated,
+            "user_id": "demo",
+        })
+    }
+
+    fn server_state(status: &str, task_state: Option<&str>, updated: &str) -> protocol::Server {
+        serde_json::from_value(server_json(status, task_state, updated)).unwrap()
+    }
+
+    async fn new_server(inner: protocol::Server) -> Server {
+        Server {
+            session: new_session().await,
+            inner,
+            flavor: protocol::ServerFlavor {
+                ephemeral_size: 0,
+                extra_specs: None,
+                original_name: "small".into(),
+                ram_size: 512,
+                root_size: 1,
+                swap_size: 0,
+                vcpu_count: 1,
+            },
+        }
+    }
+
+    fn params(query: &Query) -> Vec<(&str, &str)> {
+        query
+            .0
+            .iter()
+            .map(|(k, v)| (k.as_str(), v.as_str()))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_tags_filters() {
+        let query = new_query()
+            .await
+            .with_tags_all(vec!["a", "b"])
+            .with_tags_any(vec!["c"])
+            .without_tags(vec!["d", "e"])
+            .without_tags_any(vec!["f", "g", "h"]);
+        assert_eq!(
+            params(&query.query),
+            vec![
+                ("tags", "a,b"),
+                ("tags-any", "c"),
+                ("not-tags", "d,e"),
+                ("not-tags-any", "f,g,h"),
+            ]
+        );
+        assert_eq!(query.api_version, Some(ApiVersion(2, 26)));
+    }
+
+    #[tokio::test]
+    async fn test_ip_v6_filter() {
+        let query = new_query()
+            .await
+            .with_ip_v6("2001:db8::1".parse::<std::net::Ipv6Addr>().unwrap());
+        assert_eq!(params(&query.query), vec![("ip6", "2001:db8::1")]);
+        let detailed = query.detailed();
+        assert_eq!(params(&detailed.inner.query), vec![("ip6", "2001:db8::1")]);
+    }
+
+    #[tokio::test]
+    async fn test_name_filter_with_limit() {
+        let query = new_query().await.with_name("web-.*").with_limit(10);
+        assert_eq!(
+            params(&query.query),
+            vec![("name", "web-.*"), ("limit", "10")]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_page_size() {
+        let query = new_query().await;
+        assert_eq!(query.page_size(), 100);
+        assert_eq!(query.clone().detailed().page_size(), 50);
+
+        let query = query.with_page_size(10);
+        assert_eq!(query.page_size(), 10);
+        assert!(query.can_paginate);
+        assert!(params(&query.query).is_empty());
+        assert_eq!(query.clone().detailed().page_size(), 10);
+
+        let err = query.with_page_size(0).validate().await.unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    }
+
+    #[tokio::test]
+    async fn test_flavor_and_image_filters() {
+        let query = new_query()
+            .await
+            .with_flavor("1")
+            .with_image("cirros-id")
+            .with_status(protocol::ServerStatus::Active);
+        assert_eq!(
+            params(&query.query),
+            vec![
+                ("flavor", "1"),
+                ("image", "cirros-id"),
+                ("status", "ACTIVE")
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_all_tenants() {
+        let query = new_query().await.with_all_tenants(true);
+        assert_eq!(params(&query.query), vec![("all_tenants", "1")]);
+        let query = new_query().await.with_all_tenants(false);
+        assert!(params(&query.query).is_empty());
+
+        let query = new_query()
+            .await
+            .with_all_tenants(true)
+            .with_all_tenants(true);
+        assert_eq!(params(&query.query), vec![("all_tenants", "1")]);
+        let query = query.with_all_tenants(false);
+        assert!(params(&query.query).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_status_filter() {
+        let query = new_query().await.with_status(protocol::ServerStatus::Error);
+        assert_eq!(params(&query.query), vec![("status", "ERROR")]);
+    }
+
+    #[tokio::test]
+    async fn test_no_tags_no_api_version() {
+        let query = new_query().await.with_name("web");
+        assert_eq!(params(&query.query), vec![("name", "web")]);
+        assert!(query.api_version.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_server_links() {
+        let mut json = server_json("ACTIVE", None, "2020-01-01T00:00:00Z");
+        let server = new_server(serde_json::from_value(json.clone()).unwrap()).await;
+        assert!(server.links().is_empty());
+        assert!(server.bookmark_url().is_none());
+
+        json["links"] = serde_json::json!([
+            {"href": "http://compute/v2.1/servers/abcd", "rel": "self"},
+            {"href": "http://compute/servers/abcd", "rel": "bookmark"}
+        ]);
+        let server = new_server(serde_json::from_value(json).unwrap()).await;
+        assert_eq!(server.links().len(), 2);
+        assert_eq!(
+            server.bookmark_url().map(|url| url.as_str()),
+            Some("http://compute/servers/abcd")
+        );
+        assert!(!server.inner.extra_attributes.contains_key("links"));
+    }
+
+    #[tokio::test]
+    async fn test_reboot_waiter_sequence() {
+        let before = "2020-01-01T00:00:00Z";
+        let after = "2020-01-01T00:01:00Z";
+        let mut server = new_server(server_state("ACTIVE", None, before)).await;
+        let since = Some(server.updated_at());
+        let mut waiter = ServerStatusWaiter {
+            server: &mut server,
+            target: protocol::ServerStatus::Active,
+            unchanged_since: since,
+            idle_required: true,
+        };
+        let sequence = vec![
+            // The reboot has not been picked up yet.
+            (server_state("ACTIVE", None, before), false),
+            (server_state("ACTIVE", Some("rebooting"), before), false),
+            (server_state("REBOOT", Some("reboot_started"), after), false),
+            (server_state("ACTIVE", Some("reboot_started"), after), false),
+            (server_state("ACTIVE", None, after), true),
+        ];
+        for (inner, finished) in sequence {
+            waiter.server.inner = inner;
+            assert_eq!(waiter.check_state().unwrap().is_some(), finished);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_status_waiter() {
+        let updated = "2020-01-01T00:00:00Z";
+        let mut server = new_server(server_state("BUILD", Some("spawning"), updated)).await;
+        let mut waiter = ServerStatusWaiter::new(&mut server, protocol::ServerStatus::Active);
+        assert!(waiter.check_state().unwrap().is_none());
+        assert!(waiter
+            .timeout_error()
+            .to_string()
+            .contains("last seen in state BUILD"));
+        waiter.server.inner = server_state("ACTIVE", None, updated);
+        assert!(waiter.check_state().unwrap().is_some());
+        waiter.server.inner = server_state("ERROR", None, updated);
+        let err = waiter.check_state().unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::OperationFailed);
+    }
+
+    async fn reserved_server(id: &str, status: &str) -> Server {
+        let mut value = server_json(status, None, "2020-01-01T00:00:00Z");
+        value["id"] = id.into();
+        new_server(serde_json::from_value(value).unwrap()).await
+    }
+
+    #[tokio::test]
+    async fn test_batch_waiter() {
+        let mut waiter = ServerBatchWaiter {
+            session: new_session().await,
+            reservation_id: "r-1234".into(),
+            count: 2,
+            servers: Vec::new(),
+        };
+        assert!(waiter.check_servers().unwrap().is_none());
+
+        // Not all servers are listed yet
+        waiter.servers = vec![reserved_server("srv-1", "ACTIVE").await];
+        assert!(waiter.check_servers().unwrap().is_none());
+
+        waiter.servers = vec![
+            reserved_server("srv-1", "ACTIVE").await,
+            reserved_server("srv-2", "BUILD").await,
+        ];
+        assert!(waiter.check_servers().unwrap().is_none());
+        let err = waiter.timeout_error();
+        assert_eq!(err.error().kind(), ErrorKind::OperationTimedOut);
+        assert!(err.to_string().contains("srv-1 is ACTIVE, srv-2 is BUILD"));
+        assert_eq!(waiter.current_state().len(), 2);
+
+        waiter.servers[1] = reserved_server("srv-2", "ACTIVE").await;
+        let servers = waiter.check_servers().unwrap().unwrap();
+        let ids: Vec<_> = servers.iter().map(|srv| srv.id().as_str()).collect();
+        assert_eq!(ids, vec!["srv-1", "srv-2"]);
+
+        waiter.servers[1] = reserved_server("srv-2", "ERROR").await;
+        let err = waiter.check_servers().unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::OperationFailed);
+        assert!(err.to_string().contains("srv-2"));
+        assert!(!err.to_string().contains("srv-1"));
+    }
+
+    #[cfg(feature = "network")]
+    #[test]
+    fn test_first_port() {
+        let port = super::first_port("srv", vec!["port-1".into(), "port-2".into()]).unwrap();
+        assert_eq!(port.as_ref(), "port-1");
+
+        let err = super::first_port("srv", Vec::new()).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::ResourceNotFound);
+        assert!(err.to_string().contains("Server srv has no ports"));
+    }
+
+    #[tokio::test]
+    async fn test_for_each_server_partial_failure() {
+        let mut results = for_each_server(vec!["srv-1", "srv-2", "srv-3"], |id| async move {
+            if id == "srv-2" {
+                Err(Error::new(ErrorKind::ResourceNotFound, "gone"))
+            } else {
+                Ok(())
+            }
+        })
+        .await;
+        results.sort_by(|a, b| a.0.cmp(&b.0));
+
+        let ids: Vec<_> = results.iter().map(|(id, _)| id.as_str()).collect();
+        assert_eq!(ids, vec!["srv-1", "srv-2", "srv-3"]);
+        assert!(results[0].1.is_ok());
+        assert_eq!(
+            results[1].1.as_ref().unwrap_err().kind(),
+            ErrorKind::ResourceNotFound
+        );
+        assert!(results[2].1.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_batch_waiter_errors() {
+        let mut waiter = ServerBatchWaiter {
+            session: new_session().await,
+            reservation_id: "r-1234".into(),
+            count: 2,
+            servers: vec![
+                reserved_server("srv-1", "ACTIVE").await,
+                reserved_server("srv-2", "BUILD").await,
+            ],
+        };
+
+        // The session points at port 1, so the listing fails to connect.
+        let err = waiter.poll().await.unwrap_err();
+        assert_eq!(err.error().kind(), ErrorKind::ProtocolError);
+        assert_eq!(err.servers().len(), 2);
+
+        // The servers seen until the timeout are returned with the error.
+        let err = waiter.timeout_error();
+        let ids: Vec<_> = err.servers().iter().map(|srv| srv.id().as_str()).collect();
+        assert_eq!(ids, vec!["srv-1", "srv-2"]);
+        let (servers, inner) = err.into_parts();
+        assert_eq!(servers.len(), 2);
+        assert_eq!(inner.kind(), ErrorKind::OperationTimedOut);
+        assert_eq!(
+            Error::from(waiter.timeout_error()).kind(),
+            ErrorKind::OperationTimedOut
+        );
+    }
+
+    #[tokio::test]
+    async fn test_create_many_requires_count() {
+        let new_server = NewServer::new(new_session().await, "test".into(), "small".into());
+        let err = new_server.create_many(0).await.unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn test_reverse_raw_servers() {
+        let mut body = serde_json::json!({
+            "servers": [{"id": "1"}, {"id": "2"}],
+            "servers_links": []
+        });
+        reverse_raw_servers(&mut body);
+        assert_eq!(
+            body,
+            serde_json::json!({
+                "servers": [{"id": "2"}, {"id": "1"}],
+                "servers_links": []
+            })
+        );
+    }
+
+    #[test]
+    fn test_remote_console_expiry() {
+        let received_at = chrono::Utc.with_ymd_and_hms(2020, 1, 1, 0, 0, 0).unwrap();
+        let console = protocol::RemoteConsole {
+            protocol: protocol::ConsoleProtocol::Vnc,
+            console_type: protocol::ConsoleType::NoVnc,
+            url: "http://127.0.0.1:6080/vnc_lite.html?path=%3Ftoken%3Dabcd".into(),
+            received_at,
+        };
+        assert_eq!(
+            console.expires_at(),
+            chrono::Utc.with_ymd_and_hms(2020, 1, 1, 0, 10, 0).unwrap()
+        );
+        assert!(!console.is_expired_at(received_at + chrono::Duration::seconds(599)));
+        assert!(console.is_expired_at(received_at + chrono::Duration::seconds(600)));
+    }
+
+    #[tokio::test]
+    async fn test_server_waiter() {
+        let state = server_state("ACTIVE", None, "2020-01-01T00:00:00Z");
+        let mut server = new_server(state.clone()).await;
+        let waiter = server.wait_for_metadata_key("cloud-init-done");
+        assert!(waiter.check_server().unwrap().is_none());
+        assert!(waiter.timeout_error().to_string().contains(
+            "Timeout waiting for server abcd to have metadata key cloud-init-done, \
+             last seen in state ACTIVE"
+        ));
+
+        let mut with_key = state;
+        let _ = with_key
+            .metadata
+            .insert("cloud-init-done".into(), "2020-01-01T00:05:00Z".into());
+        waiter.server.inner = with_key;
+        assert_eq!(
+            waiter.check_server().unwrap().as_deref(),
+            Some("2020-01-01T00:05:00Z")
+        );
+
+        waiter.server.inner = server_state("ERROR", None, "2020-01-01T00:06:00Z");
+        assert_eq!(
+            waiter.check_server().unwrap_err().kind(),
+            ErrorKind::OperationFailed
+        );
+    }
+
+    #[tokio::test]
+    async fn test_stop_waiter_sequence() {
+        let mut server = new_server(server_state("ACTIVE", None, "2020-01-01T00:00:00Z")).await;
+        let mut waiter = ServerStatusWaiter::new(&mut server, protocol::ServerStatus::ShutOff);
+        let sequence = vec![
+            (
+                server_state("ACTIVE", Some("powering-off"), "2020-01-01T00:00:00Z"),
+                false,
+            ),
+            (server_state("SHUTOFF", None, "2020-01-01T00:01:00Z"), true),
+        ];
+        for (inner, finished) in sequence {
+            waiter.server.inner = inner;
+            assert_eq!(waiter.check_state().unwrap().is_some(), finished);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_stop_and_wait_already_stopped() {
+        let updated = "2020-01-01T00:00:00Z";
+        let server = new_server(server_state("SHUTOFF", None, updated)).await;
+        assert!(server.is_stopped());
+        let server = new_server(server_state("SHUTOFF", Some("powering-on"), updated)).await;
+        assert!(!server.is_stopped());
+        let server = new_server(server_state("ACTIVE", None, updated)).await;
+        assert!(!server.is_stopped());
+
+        // The cached status is not trusted: the server is refreshed first, which fails here
+        // because nothing listens on the session endpoint.
+        let mut server = new_server(server_state("SHUTOFF", None, updated)).await;
+        assert!(server
+            .stop_and_wait(std::time::Duration::from_secs(1))
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_resize_default_check() {
+        let check = power_state_unchanged(protocol::ServerPowerState::Running);
+        let mut value = server_json("VERIFY_RESIZE", None, "2020-01-01T00:00:00Z");
+        value["OS-EXT-STS:power_state"] = serde_json::json!(1);
+        let server = new_server(serde_json::from_value(value.clone()).unwrap()).await;
+        assert!(check(&server));
+        // Crashed after the resize, must be reverted.
+        value["OS-EXT-STS:power_state"] = serde_json::json!(6);
+        let server = new_server(serde_json::from_value(value).unwrap()).await;
+        assert!(!check(&server));
+    }
+
+    #[tokio::test]
+    async fn test_ensure_verifying_resize() {
+        let updated = "2020-01-01T00:00:00Z";
+        let server = new_server(server_state("VERIFY_RESIZE", None, updated)).await;
+        server.ensure_verifying_resize().unwrap();
+        // E.g. the resize was confirmed automatically.
+        let server = new_server(server_state("ACTIVE", None, updated)).await;
+        let err = server.ensure_verifying_resize().unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::OperationFailed);
+        assert!(err.to_string().contains("ACTIVE"));
+    }
+
+    #[tokio::test]
+    async fn test_server_tags() {
+        let mut value = server_json("ACTIVE", None, "2020-01-01T00:00:00Z");
+        value["tags"] = serde_json::json!(["web", "prod"]);
+        let server = new_server(serde_json::from_value(value).unwrap()).await;
+        assert_eq!(server.tags(), &vec!["web".to_string(), "prod".to_string()]);
+        assert!(server.has_tag("prod"));
+        assert!(!server.has_tag("db"));
+        let tags = server.tag_set();
+        assert!(tags.contains("web"));
+        assert_eq!(tags.len(), 2);
+
+        let server = new_server(server_state("ACTIVE", None, "2020-01-01T00:00:00Z")).await;
+        assert!(server.tags().is_empty());
+        assert!(!server.has_tag("web"));
+    }
+
+    #[test]
+    fn test_reset_state_value() {
+        assert_eq!(
+            reset_state_value(protocol::ServerStatus::Active).unwrap(),
+            "active"
+        );
+        assert_eq!(
+            reset_state_value(protocol::ServerStatus::Error).unwrap(),
+            "error"
+        );
+        assert_eq!(
+            reset_state_value(protocol::ServerStatus::ShutOff)
+                .unwrap_err()
+                .kind(),
+            ErrorKind::InvalidInput
+        );
+    }
+
+    #[tokio::test]
+    async fn test_force_stop_needs_reset() {
+        let updated = "2020-01-01T00:00:00Z";
+        let server = new_server(server_state("ACTIVE", None, updated)).await;
+        assert!(!server.needs_state_reset());
+        let server = new_server(server_state("ERROR", None, updated)).await;
+        assert!(server.needs_state_reset());
+        let server = new_server(server_state("ACTIVE", Some("powering-off"), updated)).await;
+        assert!(server.needs_state_reset());
+    }
+
+    #[tokio::test]
+    async fn test_reboot_waiter_missed_transition() {
+        let mut server = new_server(server_state("ACTIVE", None, "2020-01-01T00:00:00Z")).await;
+        let since = Some(server.updated_at());
+        let mut waiter = ServerStatusWaiter {
+            server: &mut server,
+            target: protocol::ServerStatus::Active,
+            unchanged_since: since,
+            idle_required: true,
+        };
+        waiter.server.inner = server_state("ACTIVE", None, "2020-01-01T00:01:00Z");
+        assert!(waiter.check_state().unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_status_waiter_ignores_task_state() {
+        // Only reboots wait for the task to finish.
+        let updated = "2020-01-01T00:00:00Z";
+        let mut server = new_server(server_state("SHUTOFF", None, updated)).await;
+        let mut waiter = ServerStatusWaiter::new(&mut server, protocol::ServerStatus::Active);
+        waiter.server.inner = server_state("ACTIVE", Some("powering-on"), updated);
+        assert!(waiter.check_state().unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_status_waiter_error() {
+        let mut server = new_server(server_state("REBOOT", None, "2020-01-01T00:00:00Z")).await;
+        let mut waiter = ServerStatusWaiter::new(&mut server, protocol::ServerStatus::Active);
+        waiter.server.inner = server_state("ERROR", None, "2020-01-01T00:01:00Z");
+        assert!(waiter.check_state().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_new_server_validate() {
+        let mut server = NewServer::new(new_session().await, "test".into(), "small".into());
+        assert_eq!(
+            server.validate().unwrap_err().kind(),
+            ErrorKind::InvalidInput
+        );
+        server.set_image("cirros");
+        server.validate().unwrap();
+
+        let server = NewServer::new(new_session().await, "test".into(), "small".into())
+            .with_block_device(BlockDevice::from_empty_volume(1));
+        server.validate().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_new_server_nics_order() {
+        let session = new_session().await;
+        let server = NewServer::new(session.clone(), "test".into(), "small".into())
+            .with_image("cirros")
+            .with_port(PortRef::new_verified("port-1".into()))
+            .with_network_with_fixed_ip(
+                NetworkRef::new_verified("net-1".into()),
+                "10.0.0.5".parse().unwrap(),
+            )
+            .with_port(PortRef::new_verified("port-2".into()));
+        server.validate().unwrap();
+        let networks = convert_networks(&session, server.nics).await.unwrap();
+        assert_eq!(
+            serde_json::to_value(&networks).unwrap(),
+            serde_json::json!([
+                {"port": "port-1"},
+                {"uuid": "net-1", "fixed_ip": "10.0.0.5"},
+                {"port": "port-2"}
+            ])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_convert_networks_duplicate_port() {
+        let nics = vec![
+            ServerNIC::WithPort(PortRef::new_verified("port-1".into())),
+            ServerNIC::FromNetwork(NetworkRef::new_verified("net-1".into())),
+            ServerNIC::WithPort(PortRef::new_verified("port-1".into())),
+        ];
+        let err = convert_networks(&new_session().await, nics)
+            .await
+            .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput);
+        assert!(err.to_string().contains("port-1"));
+    }
+
+    #[tokio::test]
+    async fn test_new_server_validate_missing_name() {
+        let server =
+            NewServer::new(new_session().await, String::new(), "small".into()).with_image("cirros");
+        let err = server.validate().unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput);
+        assert!(err.to_string().contains("name"));
+    }
+
+    #[tokio::test]
+    async fn test_new_server_validate_missing_flavor() {
+        let server =
+            NewServer::new(new_session().await, "test".into(), "".into()).with_image("cirros");
+        let err = server.validate().unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput);
+        assert!(err.to_string().contains("flavor"));
+    }
+
+    #[tokio::test]
+    async fn test_new_server_forced_host() {
+        let server = NewServer::new(new_session().await, "test".into(), "small".into())
+            .with_availability_zone("nova:compute-1:node-1");
+        assert_eq!(
+            server.availability_zone.as_deref(),
+            Some("nova:compute-1:node-1")
+        );
+
+        let server = NewServer::new(new_session().await, "test".into(), "small".into())
+            .with_forced_host("nova", "compute-1", Some("node-1"));
+        assert_eq!(
+            server.availability_zone.as_deref(),
+            Some("nova:compute-1:node-1")
+        );
+
+        let mut server = NewServer::new(new_session().await, "test".into(), "small".into());
+        server.set_forced_host("nova", "compute-1", None);
+        assert_eq!(server.availability_zone.as_deref(), Some("nova:compute-1"));
+    }
+
+    #[tokio::test]
+    async fn test_new_server_security_groups() {
+        let server = NewServer::new(new_session().await, "test".into(), "small".into())
+            .with_image("cirros")
+            .with_security_group("default")
+            .with_security_groups(vec!["web", "db"]);
+        assert_eq!(
+            server.security_groups,
+            Some(vec!["default".into(), "web".into(), "db".into()])
+        );
+        assert!(server.validate().is_ok());
+
+        let err = NewServer::new(new_session().await, "test".into(), "small".into())
+            .with_image("cirros")
+            .with_security_groups(Vec::<String>::new())
+            .validate()
+            .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput);
+
+        let err = NewServer::new(new_session().await, "test".into(), "small".into())
+            .with_image("cirros")
+            .with_security_group("")
+            .validate()
+            .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    }
+
+    #[tokio::test]
+    async fn test_new_server_host() {
+        let server = NewServer::new(new_session().await, "test".into(), "small".into());
+        assert!(server.host.is_none());
+        assert!(server.hypervisor_hostname.is_none());
+
+        let server = server
+            .with_host("compute-1")
+            .with_hypervisor_hostname("node-1");
+        assert_eq!(server.host.as_deref(), Some("compute-1"));
+        assert_eq!(server.hypervisor_hostname.as_deref(), Some("node-1"));
+        assert!(server.availability_zone.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_is_task_in_progress() {
+        let server = new_server(server_state("ACTIVE", None, "2020-01-01T00:00:00Z")).await;
+        assert!(!server.is_task_in_progress());
+        let server = new_server(server_state(
+            "ACTIVE",
+            Some("rebooting"),
+            "2020-01-01T00:00:00Z",
+        ))
+        .await;
+        assert!(server.is_task_in_progress());
+    }
+
+    #[tokio::test]
+    async fn test_marker_before() {
+        let query = new_query()
+            .await
+            .sort_by(Sort::Asc(protocol::ServerSortKey::DisplayName))
+            .with_marker_before("abcd");
+        assert!(!query.can_paginate);
+        let built = query.build_query(Some(10), None);
+        assert_eq!(
+            params(&built),
+            vec![
+                ("sort_key", "display_name"),
+                ("sort_dir", "desc"),
+                ("marker", "abcd"),
+                ("limit", "10"),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_marker_before_default_sort() {
+        let query = new_query().await.with_marker_before("abcd");
+        let built = query.build_query(None, None);
+        assert_eq!(
+            params(&built),
+            vec![
+                ("marker", "abcd"),
+                ("sort_key", "created_at"),
+                ("sort_dir", "asc"),
+                ("sort_key", "id"),
+                ("sort_dir", "asc"),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_floating_ips() {
+        let mut inner = server_state("ACTIVE", None, "2020-01-01T00:00:00Z");
+        inner.addresses = serde_json::from_value(serde_json::json!({
+            "private": [
+                {"addr": "192.168.0.3", "version": 4, "OS-EXT-IPS:type": "fixed"},
+                {"addr": "172.24.4.10", "version": 4, "OS-EXT-IPS:type": "floating"}
+            ]
+        }))
+        .unwrap();
+        let server = new_server(inner).await;
+        let floating = server.floating_ips();
+        assert_eq!(floating.len(), 1);
+        assert_eq!(floating[0].addr.to_string(), "172.24.4.10");
+        assert_eq!(server.floating_ip(), Some(floating[0].addr));
+        let fixed = server.fixed_ips();
+        assert_eq!(fixed.len(), 1);
+        assert_eq!(fixed[0].addr.to_string(), "192.168.0.3");
+        assert_eq!(server.addresses()["private"].len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_progress() {
+        let server = new_server(server_state("ACTIVE", None, "2020-01-01T00:00:00Z")).await;
+        assert_eq!(server.progress(), None);
+
+        let mut value = server_json("RESIZE", Some("resize_migrating"), "2020-01-01T00:00:00Z");
+        value["progress"] = 45.into();
+        let server = new_server(serde_json::from_value(value).unwrap()).await;
+        assert_eq!(server.progress(), Some(45));
+    }
+
+    #[tokio::test]
+    async fn test_extra_attributes() {
+        let mut value = server_json("ACTIVE", None, "2020-01-01T00:00:00Z");
+        value["OS-EXT-FOO:bar"] = "baz".into();
+        let server = new_server(serde_json::from_value(value).unwrap()).await;
+        let extra = server.extra_attributes();
+        assert_eq!(extra.get("OS-EXT-FOO:bar"), Some(&"baz".into()));
+        assert!(!extra.contains_key("status"));
+        assert!(!extra.contains_key("OS-EXT-STS:task_state"));
+    }
+
+    #[tokio::test]
+    async fn test_age() {
+        let server = new_server(server_state("ACTIVE", None, "2020-01-01T00:00:00Z")).await;
+        let now = chrono::DateTime::parse_from_rfc3339("2020-01-11T12:00:00Z")
+            .unwrap()
+            .with_timezone(&chrono::Utc);
+        assert_eq!(
+            server.age_at(now),
+            chrono::Duration::days(10) + chrono::Duration::hours(12)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_timestamps() {
+        let server = new_server(server_state("ACTIVE", None, "2020-01-02T03:04:05+02:00")).await;
+        assert_eq!(
+            server.created_at().with_timezone(&chrono::Utc),
+            chrono::Utc.with_ymd_and_hms(2020, 1, 1, 0, 0, 0).unwrap()
+        );
+        assert_eq!(
+            server.updated_at().with_timezone(&chrono::Utc),
+            chrono::Utc.with_ymd_and_hms(2020, 1, 2, 1, 4, 5).unwrap()
+        );
+
+        let json = server_json("ACTIVE", None, "yesterday");
+        assert!(serde_json::from_value::<protocol::Server>(json).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_to_data() {
+        let mut json = server_json("ACTIVE", None, "2020-01-02T00:00:00Z");
+        json["image"] = serde_json::json!({"id": "img", "links": []});
+        json["metadata"] = serde_json::json!({"role": "db"});
+        let server = new_server(serde_json::from_value(json).unwrap()).await;
+        let data = server.to_data();
+        assert_eq!(&data.id, server.id());
+        assert_eq!(&data.name, server.name());
+        assert_eq!(data.status, server.status());
+        assert_eq!(data.power_state, server.power_state());
+        assert_eq!(&data.availability_zone, server.availability_zone());
+        assert_eq!(data.created_at, server.created_at());
+        assert_eq!(data.updated_at, server.updated_at());
+        assert_eq!(data.image_id.as_ref(), server.image_id());
+        assert_eq!(&data.metadata, server.metadata());
+        assert_eq!(data.flavor.original_name, "small");
+        assert_eq!(data.task_state, None);
+    }
+
+    #[test]
+    fn test_aggregate_usage_shared_flavor() {
+        let mut other = server_json("ACTIVE", None, "2020-01-01T00:00:00Z");
+        other["id"] = "efgh".into();
+        let servers = vec![
+            server_state("ACTIVE", None, "2020-01-01T00:00:00Z"),
+            serde_json::from_value(other).unwrap(),
+        ];
+        let ids = flavor_ids(&servers);
+        assert_eq!(ids.len(), 1);
+
+        let flavor: protocol::Flavor = serde_json::from_value(serde_json::json!({
+            "disk": 20,
+            "id": "1",
+            "name": "m1.small",
+            "ram": 2048,
+            "rxtx_factor": 1.0,
+            "swap": "",
+            "vcpus": 2
+        }))
+        .unwrap();
+        let flavors = vec![("1".to_string(), flavor)].into_iter().collect();
+        assert_eq!(
+            sum_usage(&servers, &flavors).unwrap(),
+            ResourceTotals {
+                ram_size: 4096,
+                root_size: 40,
+                vcpu_count: 4,
+            }
+        );
+
+        // A server must not be silently skipped.
+        let err = sum_usage(&servers, &HashMap::new()).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::ResourceNotFound);
+        assert!(err.to_string().contains("abcd"));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_flavors_once() {
+        let mut servers = Vec::new();
+        for (id, flavor) in &[
+            ("srv-1", "1"),
+            ("srv-2", "1"),
+            ("srv-3", "2"),
+            ("srv-4", "1"),
+        ] {
+            let mut value = server_json("ACTIVE", None, "2020-01-01T00:00:00Z");
+            value["id"] = (*id).into();
+            value["flavor"]["id"] = (*flavor).into();
+            servers.push(serde_json::from_value(value).unwrap());
+        }
+
+        let calls = std::sync::Mutex::new(Vec::new());
+        let flavors = fetch_flavors(&servers, |id| {
+            calls.lock().unwrap().push(id.to_string());
+            async move {
+                Ok(serde_json::from_value(serde_json::json!({
+                    "disk": 20,
+                    "id": id,
+                    "name": "flavor",
+                    "ram": 2048,
+                    "rxtx_factor": 1.0,
+                    "swap": "",
+                    "vcpus": 2
+                }))
+                .unwrap())
+            }
+        })
+        .await
+        .unwrap();
+
+        let mut calls = calls.into_inner().unwrap();
+        calls.sort();
+        assert_eq!(calls, vec!["1", "2"]);
+        assert_eq!(flavors.len(), 2);
+        assert_eq!(flavors["2"].id, "2");
+    }
+
+    #[tokio::test]
+    async fn test_deleted_at() {
+        let server = new_server(server_state("ACTIVE", None, "2020-01-01T00:00:00Z")).await;
+        assert_eq!(server.deleted_at(), None);
+
+        let mut json = server_json("DELETED", None, "2020-01-05T10:00:00Z");
+        json["deleted_at"] = "2020-01-05T10:00:00Z".into();
+        let server = new_server(serde_json::from_value(json).unwrap()).await;
+        let deleted_at = chrono::DateTime::parse_from_rfc3339("2020-01-05T10:00:00Z").unwrap();
+        assert_eq!(server.deleted_at(), Some(deleted_at));
+        assert!(server.was_deleted_between(
+            deleted_at - chrono::Duration::hours(1),
+            deleted_at.with_timezone(&chrono::Utc)
+        ));
+        assert!(!server.was_deleted_between(
+            deleted_at + chrono::Duration::seconds(1),
+            deleted_at + chrono::Duration::hours(1)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_write_ndjson_line() {
+        let mut other = server_json("SHUTOFF", None, "2020-01-01T00:00:00Z");
+        other["id"] = "efgh".into();
+        let servers = vec![
+            new_server(server_state("ACTIVE", None, "2020-01-01T00:00:00Z")).await,
+            new_server(serde_json::from_value(other).unwrap()).await,
+        ];
+        let mut buffer = Vec::new();
+        for server in &servers {
+            write_ndjson_line(&mut buffer, &server.to_data()).unwrap();
+        }
+        let output = String::from_utf8(buffer).unwrap();
+        let lines: Vec<serde_json::Value> = output
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["id"], "abcd");
+        assert_eq!(lines[1]["id"], "efgh");
+        assert_eq!(lines[1]["status"], "SHUTOFF");
+    }
+
+    /// Serves two pages of servers.
+    struct TwoPages;
+
+    #[async_trait]
+    impl ResourceQuery for TwoPages {
+        type Item = Server;
+
+        const DEFAULT_LIMIT: usize = 2;
+
+        async fn can_paginate(&self) -> Result<bool> {
+            Ok(true)
+        }
+
+        fn extract_marker(&self, resource: &Server) -> String {
+            resource.id().clone()
+        }
+
+        async fn fetch_chunk(
+            &self,
+            _limit: Option<usize>,
+            marker: Option<String>,
+        ) -> Result<Vec<Server>> {
+            Ok(match marker.as_deref() {
+                None => vec![
+                    reserved_server("srv-1", "ACTIVE").await,
+                    reserved_server("srv-2", "ACTIVE").await,
+                ],
+                Some("srv-2") => vec![reserved_server("srv-3", "SHUTOFF").await],
+                _ => Vec::new(),
+            })
+        }
+    }
+
+    /// Records the amount of data written at each flush.
+    #[derive(Default)]
+    struct FlushRecorder {
+        buffer: Vec<u8>,
+        flushed_at: Vec<usize>,
+    }
+
+    impl Write for FlushRecorder {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.buffer.write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            self.flushed_at.push(self.buffer.len());
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_write_ndjson_pages() {
+        let mut recorder = FlushRecorder::default();
+        write_ndjson_pages(ResourceIterator::new(TwoPages), &mut recorder)
+            .await
+            .unwrap();
+        let output = String::from_utf8(recorder.buffer).unwrap();
+        let ids: Vec<_> = output
+            .lines()
+            .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap()["id"].clone())
+            .collect();
+        assert_eq!(ids, vec!["srv-1", "srv-2", "srv-3"]);
+        // Flushed once per page: after the second line and at the end.
+        let second_line_end = output.match_indices('\n').nth(1).unwrap().0 + 1;
+        assert_eq!(recorder.flushed_at, vec![second_line_end, output.len()]);
+    }
+}
@@ -37,6 +37,48 @@ pub async fn get_image<S: AsRef<str>>(session: &Session, id_or_name: S) -> Resul
     }
 }
 
+/// Add a member to an image.
+pub async fn add_image_member<S1, S2>(session: &Session, id: S1, member: S2) -> Result<ImageMember>
+where
+    S1: AsRef<str>,
+    S2: Into<String>,
+{
+    let body = ImageMemberCreate {
+        member: member.into(),
+    };
+    debug!("Adding member {} to image {}", body.member, id.as_ref());
+    let member: ImageMember = session
+        .post(IMAGE, &["images", id.as_ref(), "members"])
+        .json(&body)
+        .fetch()
+        .await?;
+    debug!("Added image member {:?}", member);
+    Ok(member)
+}
+
+/// Remove a member from an image.
+pub async fn delete_image_member<S1, S2>(session: &Session, id: S1, member: S2) -> Result<()>
+where
+    S1: AsRef<str>,
+    S2: AsRef<str>,
+{
+    trace!(
+        "Removing member {} from image {}",
+        member.as_ref(),
+        id.as_ref()
+    );
+    let _ = session
+        .delete(IMAGE, &["images", id.as_ref(), "members", member.as_ref()])
+        .send()
+        .await?;
+    debug!(
+        "Successfully removed member {} from image {}",
+        member.as_ref(),
+        id.as_ref()
+    );
+    Ok(())
+}
+
 /// Get an image by its ID.
 pub async fn get_image_by_id<S: AsRef<str>>(session: &Session, id: S) -> Result<Image> {
     trace!("Fetching image {}", id.as_ref());
@@ -62,6 +104,39 @@ pub async fn get_image_by_name<S: AsRef<str>>(session: &Session, name: S) -> Res
     Ok(result)
 }
 
+/// Start importing data into an image.
+pub async fn import_image<S: AsRef<str>>(
+    session: &Session,
+    id: S,
+    request: ImageImport,
+) -> Result<()> {
+    debug!(
+        "Importing data into image {} with {:?}",
+        id.as_ref(),
+        request
+    );
+    let _ = session
+        .post(IMAGE, &["images", id.as_ref(), "import"])
+        .json(&request)
+        .send()
+        .await?;
+    debug!("Successfully started import into image {}", id.as_ref());
+    Ok(())
+}
+
+/// List members of an image.
+pub async fn list_image_members<S: AsRef<str>>(
+    session: &Session,
+    id: S,
+) -> Result<Vec<ImageMember>> {
+    trace!("Listing members of image {}", id.as_ref());
+    let root: ImageMembersRoot = session
+        .get_json(IMAGE, &["images", id.as_ref(), "members"])
+        .await?;
+    trace!("Received image members: {:?}", root.members);
+    Ok(root.members)
+}
+
 /// List images.
 pub async fn list_images<Q: Serialize + Sync + Debug>(
     session: &Session,
@@ -72,3 +147,30 @@ pub async fn list_images<Q: Serialize + Sync + Debug>(
     trace!("Received images: {:?}", root.images);
     Ok(root.images)
 }
+
+/// Update the status of an image member.
+pub async fn update_image_member<S1, S2>(
+    session: &Session,
+    id: S1,
+    member: S2,
+    status: ImageMemberStatus,
+) -> Result<ImageMember>
+where
+    S1: AsRef<str>,
+    S2: AsRef<str>,
+{
+    debug!(
+        "Setting status of member {} of image {} to {}",
+        member.as_ref(),
+        id.as_ref(),
+        status
+    );
+    let body = ImageMemberUpdate { status };
+    let member: ImageMember = session
+        .put(IMAGE, &["images", id.as_ref(), "members", member.as_ref()])
+        .json(&body)
+        .fetch()
+        .await?;
+    debug!("Updated image member {:?}", member);
+    Ok(member)
+}
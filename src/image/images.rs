@@ -14,15 +14,18 @@
 
 //! Image management via Image API.
 
+use std::time::Duration;
+
 use async_trait::async_trait;
 use chrono::{DateTime, FixedOffset};
 use futures::stream::{Stream, TryStreamExt};
 
-use super::super::common::{ImageRef, Refresh, ResourceIterator, ResourceQuery};
+use super::super::common::{ImageRef, ProjectRef, Refresh, ResourceIterator, ResourceQuery};
 use super::super::session::Session;
 use super::super::utils::Query;
-use super::super::{Result, Sort};
-use super::{api, protocol};
+use super::super::waiter::Waiter;
+use super::super::{Error, ErrorKind, Result, Sort};
+use super::{api, protocol, ImageMember};
 
 /// A query to image list.
 #[derive(Clone, Debug)]
@@ -40,6 +43,12 @@ pub struct Image {
     inner: protocol::Image,
 }
 
+/// Waiter for an image to become active.
+#[derive(Debug)]
+pub struct ImageStatusWaiter {
+    image: Image,
+}
+
 impl Image {
     /// Create an Image object.
     pub(crate) async fn new<Id: AsRef<str>>(session: Session, id: Id) -> Result<Image> {
@@ -120,6 +129,71 @@ impl Image {
         #[doc = "Image visibility."]
         visibility: protocol::ImageVisibility
     }
+
+    /// Create a waiter for this image to become active.
+    pub fn active_waiter(&self) -> ImageStatusWaiter {
+        ImageStatusWaiter {
+            image: self.clone(),
+        }
+    }
+
+    /// Wait for this image to become active.
+    ///
+    /// Fails with `OperationFailed` if the image gets killed or deleted instead.
+    /// Use `active_waiter` to configure the polling interval.
+    pub async fn wait_for_active(&self, timeout: Duration) -> Result<Image> {
+        self.active_waiter().wait_for(timeout).await
+    }
+
+    /// Start importing data into this image.
+    ///
+    /// The import happens asynchronously: the image becomes `active` once it is finished,
+    /// see `wait_for_active`.
+    pub async fn import(&self, method: protocol::ImageImportMethod) -> Result<()> {
+        api::import_image(&self.session, &self.inner.id, method.into()).await
+    }
+
+    /// List projects this image is shared with.
+    pub async fn members(&self) -> Result<Vec<ImageMember>> {
+        Ok(api::list_image_members(&self.session, &self.inner.id)
+            .await?
+            .into_iter()
+            .map(ImageMember::new)
+            .collect())
+    }
+
+    /// Share this image with a project.
+    ///
+    /// Fails with `InvalidInput` unless the image has `shared` visibility.
+    pub async fn add_member<P: Into<ProjectRef>>(&self, project: P) -> Result<ImageMember> {
+        if self.inner.visibility != protocol::ImageVisibility::Shared {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "Image {} has visibility {}, only shared images can have members",
+                    self.inner.id, self.inner.visibility
+                ),
+            ));
+        }
+        let member = api::add_image_member(&self.session, &self.inner.id, project.into()).await?;
+        Ok(ImageMember::new(member))
+    }
+
+    /// Stop sharing this image with a project.
+    pub async fn remove_member<P: Into<ProjectRef>>(&self, project: P) -> Result<()> {
+        api::delete_image_member(&self.session, &self.inner.id, project.into()).await
+    }
+
+    /// Accept, reject or reset to pending the sharing of this image with a project.
+    pub async fn update_member_status<P: Into<ProjectRef>>(
+        &self,
+        project: P,
+        status: protocol::ImageMemberStatus,
+    ) -> Result<ImageMember> {
+        let member =
+            api::update_image_member(&self.session, &self.inner.id, project.into(), status).await?;
+        Ok(ImageMember::new(member))
+    }
 }
 
 #[async_trait]
@@ -131,6 +205,73 @@ impl Refresh for Image {
     }
 }
 
+#[async_trait]
+impl Waiter<Image, Error> for ImageStatusWaiter {
+    fn default_wait_timeout(&self) -> Option<Duration> {
+        Some(Duration::new(1800, 0))
+    }
+
+    fn default_delay(&self) -> Duration {
+        Duration::new(5, 0)
+    }
+
+    fn timeout_error(&self) -> Error {
+        Error::new(
+            ErrorKind::OperationTimedOut,
+            format!(
+                "Timeout waiting for image {} to become active",
+                self.image.id()
+            ),
+        )
+    }
+
+    async fn poll(&mut self) -> Result<Option<Image>> {
+        self.image.refresh().await?;
+        self.check_state()
+    }
+}
+
+impl ImageStatusWaiter {
+    /// Current state of the image.
+    pub fn current_state(&self) -> &Image {
+        &self.image
+    }
+
+    fn check_state(&self) -> Result<Option<Image>> {
+        match self.image.status() {
+            protocol::ImageStatus::Active => {
+                debug!("Image {} is active", self.image.id());
+                Ok(Some(self.image.clone()))
+            }
+            protocol::ImageStatus::Killed
+            | protocol::ImageStatus::Deleted
+            | protocol::ImageStatus::PendingDelete => {
+                debug!(
+                    "Image {} will not become active - status is {}",
+                    self.image.id(),
+                    self.image.status()
+                );
+                Err(Error::new(
+                    ErrorKind::OperationFailed,
+                    format!(
+                        "Image {} got into {} state",
+                        self.image.id(),
+                        self.image.status()
+                    ),
+                ))
+            }
+            other => {
+                trace!(
+                    "Still waiting for image {} to become active, current is {}",
+                    self.image.id(),
+                    other
+                );
+                Ok(None)
+            }
+        }
+    }
+}
+
 impl ImageQuery {
     pub(crate) fn new(session: Session) -> ImageQuery {
         ImageQuery {
@@ -268,3 +409,51 @@ impl ImageRef {
         })
     }
 }
+
+#[cfg(test)]
+mod test {
+    use osauth::NoAuth;
+
+    use super::super::super::session::Session;
+    use super::super::protocol;
+    use super::{Image, ImageStatusWaiter};
+
+    fn image_state(status: &str) -> protocol::Image {
+        serde_json::from_value(serde_json::json!({
+            "created_at": "2020-01-01T00:00:00Z",
+            "id": "abcd",
+            "name": "cirros",
+            "status": status,
+            "updated_at": "2020-01-01T00:00:00Z",
+            "visibility": "private"
+        }))
+        .unwrap()
+    }
+
+    async fn new_waiter(status: &str) -> ImageStatusWaiter {
+        let auth = NoAuth::new("http://127.0.0.1:9292").unwrap();
+        ImageStatusWaiter {
+            image: Image {
+                session: Session::new(auth).await.unwrap(),
+                inner: image_state(status),
+            },
+        }
+    }
+
+    #[tokio::test]
+    async fn test_image_waiter_sequence() {
+        let mut waiter = new_waiter("queued").await;
+        assert!(waiter.check_state().unwrap().is_none());
+        waiter.image.inner = image_state("saving");
+        assert!(waiter.check_state().unwrap().is_none());
+        waiter.image.inner = image_state("active");
+        let image = waiter.check_state().unwrap().expect("image is active");
+        assert_eq!(image.status(), protocol::ImageStatus::Active);
+    }
+
+    #[tokio::test]
+    async fn test_image_waiter_killed() {
+        let waiter = new_waiter("killed").await;
+        assert!(waiter.check_state().is_err());
+    }
+}
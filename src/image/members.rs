@@ -0,0 +1,89 @@
+// Copyright 2026 Dmitry Tantsur <divius.inside@gmail.com>
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Image sharing via Image API.
+
+use chrono::{DateTime, FixedOffset};
+
+use super::protocol;
+
+/// A project that an image is shared with.
+#[derive(Clone, Debug)]
+pub struct ImageMember {
+    inner: protocol::ImageMember,
+}
+
+impl ImageMember {
+    pub(crate) fn new(inner: protocol::ImageMember) -> ImageMember {
+        ImageMember { inner }
+    }
+
+    transparent_property! {
+        #[doc = "Creation date and time."]
+        created_at: DateTime<FixedOffset>
+    }
+
+    transparent_property! {
+        #[doc = "ID of the shared image."]
+        image_id: ref String
+    }
+
+    transparent_property! {
+        #[doc = "ID of the project the image is shared with."]
+        member_id: ref String
+    }
+
+    transparent_property! {
+        #[doc = "Membership status."]
+        status: protocol::ImageMemberStatus
+    }
+
+    transparent_property! {
+        #[doc = "Last update date and time."]
+        updated_at: DateTime<FixedOffset>
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::protocol;
+    use super::ImageMember;
+
+    #[test]
+    fn test_image_member_parse() {
+        let member: protocol::ImageMember = serde_json::from_value(serde_json::json!({
+            "created_at": "2016-09-15T13:40:04Z",
+            "image_id": "d7ed5a2a-3e6c-4a1e-9b8c-1b4cf0e8cbe3",
+            "member_id": "b4d7ac2e5a8a4ad6a5d0f4c9d2ab0e1c",
+            "schema": "/v2/schemas/member",
+            "status": "pending",
+            "updated_at": "2016-09-15T13:40:04Z"
+        }))
+        .unwrap();
+        let member = ImageMember::new(member);
+        assert_eq!(member.member_id(), "b4d7ac2e5a8a4ad6a5d0f4c9d2ab0e1c");
+        assert_eq!(member.status(), protocol::ImageMemberStatus::Pending);
+    }
+
+    #[test]
+    fn test_image_member_create_body() {
+        let body = protocol::ImageMemberCreate {
+            member: "b4d7ac2e5a8a4ad6a5d0f4c9d2ab0e1c".into(),
+        };
+        assert_eq!(
+            serde_json::to_value(&body).unwrap(),
+            serde_json::json!({"member": "b4d7ac2e5a8a4ad6a5d0f4c9d2ab0e1c"})
+        );
+    }
+}
@@ -16,9 +16,12 @@
 
 mod api;
 mod images;
+mod members;
 mod protocol;
 
-pub use self::images::{Image, ImageQuery};
+pub use self::images::{Image, ImageQuery, ImageStatusWaiter};
+pub use self::members::ImageMember;
 pub use self::protocol::{
-    ImageContainerFormat, ImageDiskFormat, ImageSortKey, ImageStatus, ImageVisibility,
+    ImageContainerFormat, ImageDiskFormat, ImageImportMethod, ImageMemberStatus, ImageSortKey,
+    ImageStatus, ImageVisibility,
 };
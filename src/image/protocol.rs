@@ -19,7 +19,7 @@
 
 use chrono::{DateTime, FixedOffset};
 use reqwest::Url;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 use super::super::common;
 
@@ -46,6 +46,15 @@ protocol_enum! {
     }
 }
 
+protocol_enum! {
+    #[doc = "Possible statuses of an image member."]
+    enum ImageMemberStatus {
+        Accepted = "accepted",
+        Pending = "pending",
+        Rejected = "rejected"
+    }
+}
+
 protocol_enum! {
     #[doc = "Possible container formats."]
     enum ImageContainerFormat {
@@ -92,6 +101,16 @@ impl Default for ImageSortKey {
     }
 }
 
+/// A method to import image data.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ImageImportMethod {
+    /// Import data previously uploaded to the staging area.
+    GlanceDirect,
+    /// Download data from the given URL.
+    WebDownload(Url),
+}
+
 /// An image.
 #[derive(Debug, Clone, Deserialize)]
 pub struct Image {
@@ -126,3 +145,91 @@ pub struct Image {
 pub struct ImagesRoot {
     pub images: Vec<Image>,
 }
+
+/// An image member.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ImageMember {
+    pub created_at: DateTime<FixedOffset>,
+    pub image_id: String,
+    pub member_id: String,
+    pub status: ImageMemberStatus,
+    pub updated_at: DateTime<FixedOffset>,
+}
+
+/// A list of image members.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ImageMembersRoot {
+    pub members: Vec<ImageMember>,
+}
+
+/// A request to add an image member.
+#[derive(Debug, Clone, Serialize)]
+pub struct ImageMemberCreate {
+    pub member: String,
+}
+
+/// A request to update an image member.
+#[derive(Debug, Clone, Serialize)]
+pub struct ImageMemberUpdate {
+    pub status: ImageMemberStatus,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ImageImportMethodBody {
+    pub name: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub uri: Option<String>,
+}
+
+/// A request to import image data.
+#[derive(Debug, Clone, Serialize)]
+pub struct ImageImport {
+    pub method: ImageImportMethodBody,
+}
+
+impl From<ImageImportMethod> for ImageImport {
+    fn from(value: ImageImportMethod) -> ImageImport {
+        let method = match value {
+            ImageImportMethod::GlanceDirect => ImageImportMethodBody {
+                name: "glance-direct",
+                uri: None,
+            },
+            ImageImportMethod::WebDownload(url) => ImageImportMethodBody {
+                name: "web-download",
+                uri: Some(url.into()),
+            },
+        };
+        ImageImport { method }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use reqwest::Url;
+
+    use super::{ImageImport, ImageImportMethod};
+
+    #[test]
+    fn test_image_import_web_download() {
+        let url = Url::parse("https://example.com/cirros.qcow2").unwrap();
+        let body: ImageImport = ImageImportMethod::WebDownload(url).into();
+        assert_eq!(
+            serde_json::to_value(&body).unwrap(),
+            serde_json::json!({
+                "method": {
+                    "name": "web-download",
+                    "uri": "https://example.com/cirros.qcow2"
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn test_image_import_glance_direct() {
+        let body: ImageImport = ImageImportMethod::GlanceDirect.into();
+        assert_eq!(
+            serde_json::to_value(&body).unwrap(),
+            serde_json::json!({"method": {"name": "glance-direct"}})
+        );
+    }
+}
@@ -633,6 +633,61 @@ macro_rules! protocol_enum {
             }
         }
     );
+
+    {$(#[$attr:meta])* enum $name:ident {
+        $($(#[$iattr:meta])* $item:ident = $val:expr),+;
+        $(#[$oattr:meta])* $other:ident(String)
+    }} => (
+        $(#[$attr])*
+        #[derive(Debug, Clone, PartialEq, Eq)]
+        #[non_exhaustive]
+        pub enum $name {
+            $($(#[$iattr])* $item),+,
+            $(#[$oattr])* $other(String),
+        }
+
+        impl $name {
+            fn as_ref(&self) -> &str {
+                match *self {
+                    $($name::$item => $val),+,
+                    $name::$other(ref value) => value,
+                }
+            }
+        }
+
+        impl<'de> ::serde::de::Deserialize<'de> for $name {
+            fn deserialize<D>(deserializer: D) -> ::std::result::Result<Self, D::Error>
+                    where D: ::serde::de::Deserializer<'de> {
+                let value = String::deserialize(deserializer)?;
+                Ok(match value.as_ref() {
+                    $($val => $name::$item),+,
+                    _ => $name::$other(value),
+                })
+            }
+        }
+
+        impl ::std::fmt::Display for $name {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+                f.write_str(self.as_ref())
+            }
+        }
+
+        impl ::serde::ser::Serialize for $name {
+            fn serialize<S>(&self, serializer: S) -> ::std::result::Result<S::Ok, S::Error>
+                    where S: ::serde::ser::Serializer {
+                serializer.serialize_str(self.as_ref())
+            }
+        }
+
+        impl From<$name> for String {
+            fn from(value: $name) -> String {
+                match value {
+                    $name::$other(value) => value,
+                    known => String::from(known.as_ref()),
+                }
+            }
+        }
+    );
 }
 
 /// Reimports of authentication bits from `osauth`.
@@ -640,7 +695,7 @@ macro_rules! protocol_enum {
 /// See [osauth documentation](https://docs.rs/osauth/) for details.
 pub mod auth {
     pub use osauth::identity::{Password, Scope, Token};
-    pub use osauth::{AuthType, NoAuth};
+    pub use osauth::{AuthType, CloudConfig, NoAuth};
 }
 mod cloud;
 pub mod common;
@@ -657,6 +712,7 @@ pub mod session {
     pub use osauth::services::ServiceType;
     pub use osauth::Session;
 }
+mod registry;
 mod utils;
 pub mod waiter;
 
@@ -668,6 +724,7 @@ pub type Result<T> = ::std::result::Result<T, Error>;
 
 pub use crate::cloud::Cloud;
 pub use crate::common::Refresh;
+pub use crate::registry::CloudRegistry;
 
 /// Sorting request.
 #[derive(Debug, Clone)]
@@ -149,6 +149,21 @@ pub async fn create_port(session: &Session, request: Port) -> Result<Port> {
     Ok(root.port)
 }
 
+/// Create an RBAC policy.
+pub async fn create_rbac_policy(session: &Session, request: RbacPolicy) -> Result<RbacPolicy> {
+    debug!("Creating a new RBAC policy with {:?}", request);
+    let body = RbacPolicyRoot {
+        rbac_policy: request,
+    };
+    let root: RbacPolicyRoot = session
+        .post(NETWORK, &["rbac-policies"])
+        .json(&body)
+        .fetch()
+        .await?;
+    debug!("Created RBAC policy {:?}", root.rbac_policy);
+    Ok(root.rbac_policy)
+}
+
 /// Create a router.
 pub async fn create_router(session: &Session, request: Router) -> Result<Router> {
     debug!("Creating a new router with {:?}", request);
@@ -163,9 +178,9 @@ pub async fn create_router(session: &Session, request: Router) -> Result<Router>
 }
 
 /// Create a subnet.
-pub async fn create_subnet(session: &Session, request: Subnet) -> Result<Subnet> {
+pub async fn create_subnet(session: &Session, request: SubnetCreate) -> Result<Subnet> {
     debug!("Creating a new subnet with {:?}", request);
-    let body = SubnetRoot { subnet: request };
+    let body = SubnetCreateRoot { subnet: request };
     let root: SubnetRoot = session
         .post(NETWORK, &["subnets"])
         .json(&body)
@@ -175,6 +190,21 @@ pub async fn create_subnet(session: &Session, request: Subnet) -> Result<Subnet>
     Ok(root.subnet)
 }
 
+/// Create a subnet pool.
+pub async fn create_subnet_pool(session: &Session, request: SubnetPool) -> Result<SubnetPool> {
+    debug!("Creating a new subnet pool with {:?}", request);
+    let body = SubnetPoolRoot {
+        subnetpool: request,
+    };
+    let root: SubnetPoolRoot = session
+        .post(NETWORK, &["subnetpools"])
+        .json(&body)
+        .fetch()
+        .await?;
+    debug!("Created subnet pool {:?}", root.subnetpool);
+    Ok(root.subnetpool)
+}
+
 /// Delete a floating IP.
 pub async fn delete_floating_ip<S: AsRef<str>>(session: &Session, id: S) -> Result<()> {
     debug!("Deleting floating IP {}", id.as_ref());
@@ -208,6 +238,17 @@ pub async fn delete_port<S: AsRef<str>>(session: &Session, id: S) -> Result<()>
     Ok(())
 }
 
+/// Delete an RBAC policy.
+pub async fn delete_rbac_policy<S: AsRef<str>>(session: &Session, id: S) -> Result<()> {
+    debug!("Deleting RBAC policy {}", id.as_ref());
+    let _ = session
+        .delete(NETWORK, &["rbac-policies", id.as_ref()])
+        .send()
+        .await?;
+    debug!("RBAC policy {} was deleted", id.as_ref());
+    Ok(())
+}
+
 /// Delete a router.
 pub async fn delete_router<S: AsRef<str>>(session: &Session, id: S) -> Result<()> {
     debug!("Deleting router {}", id.as_ref());
@@ -230,6 +271,17 @@ pub async fn delete_subnet<S: AsRef<str>>(session: &Session, id: S) -> Result<()
     Ok(())
 }
 
+/// Delete a subnet pool.
+pub async fn delete_subnet_pool<S: AsRef<str>>(session: &Session, id: S) -> Result<()> {
+    debug!("Deleting subnet pool {}", id.as_ref());
+    let _ = session
+        .delete(NETWORK, &["subnetpools", id.as_ref()])
+        .send()
+        .await?;
+    debug!("Subnet pool {} was deleted", id.as_ref());
+    Ok(())
+}
+
 /// Get a floating IP.
 pub async fn get_floating_ip<S: AsRef<str>>(session: &Session, id: S) -> Result<FloatingIp> {
     trace!("Get floating IP by ID {}", id.as_ref());
@@ -314,6 +366,16 @@ pub async fn get_port_by_name<S: AsRef<str>>(session: &Session, name: S) -> Resu
     Ok(result)
 }
 
+/// Get an RBAC policy.
+pub async fn get_rbac_policy<S: AsRef<str>>(session: &Session, id: S) -> Result<RbacPolicy> {
+    trace!("Get RBAC policy by ID {}", id.as_ref());
+    let root: RbacPolicyRoot = session
+        .get_json(NETWORK, &["rbac-policies", id.as_ref()])
+        .await?;
+    trace!("Received {:?}", root.rbac_policy);
+    Ok(root.rbac_policy)
+}
+
 /// Get a router.
 pub async fn get_router<S: AsRef<str>>(session: &Session, id_or_name: S) -> Result<Router> {
     let s = id_or_name.as_ref();
@@ -388,6 +450,51 @@ pub async fn get_subnet_by_name<S: AsRef<str>>(session: &Session, name: S) -> Re
     Ok(result)
 }
 
+/// Get a subnet pool.
+pub async fn get_subnet_pool<S: AsRef<str>>(
+    session: &Session,
+    id_or_name: S,
+) -> Result<SubnetPool> {
+    let s = id_or_name.as_ref();
+    match get_subnet_pool_by_id(session, s).await {
+        Ok(value) => Ok(value),
+        Err(err) if err.kind() == ErrorKind::ResourceNotFound => {
+            get_subnet_pool_by_name(session, s).await
+        }
+        Err(err) => Err(err),
+    }
+}
+
+/// Get a subnet pool by its ID.
+pub async fn get_subnet_pool_by_id<S: AsRef<str>>(session: &Session, id: S) -> Result<SubnetPool> {
+    trace!("Get subnet pool by ID {}", id.as_ref());
+    let root: SubnetPoolRoot = session
+        .get_json(NETWORK, &["subnetpools", id.as_ref()])
+        .await?;
+    trace!("Received {:?}", root.subnetpool);
+    Ok(root.subnetpool)
+}
+
+/// Get a subnet pool by its name.
+pub async fn get_subnet_pool_by_name<S: AsRef<str>>(
+    session: &Session,
+    name: S,
+) -> Result<SubnetPool> {
+    trace!("Get subnet pool by name {}", name.as_ref());
+    let root: SubnetPoolsRoot = session
+        .get(NETWORK, &["subnetpools"])
+        .query(&[("name", name.as_ref())])
+        .fetch()
+        .await?;
+    let result = utils::one(
+        root.subnetpools,
+        "Subnet pool with given name or ID not found",
+        "Too many subnet pools found with given name",
+    )?;
+    trace!("Received {:?}", result);
+    Ok(result)
+}
+
 /// List floating IPs.
 pub async fn list_floating_ips<Q: Serialize + Sync + Debug>(
     session: &Session,
@@ -433,6 +540,21 @@ pub async fn list_ports<Q: Serialize + Sync + Debug>(
     Ok(root.ports)
 }
 
+/// List RBAC policies.
+pub async fn list_rbac_policies<Q: Serialize + Sync + Debug>(
+    session: &Session,
+    query: &Q,
+) -> Result<Vec<RbacPolicy>> {
+    trace!("Listing RBAC policies with {:?}", query);
+    let root: RbacPoliciesRoot = session
+        .get(NETWORK, &["rbac-policies"])
+        .query(query)
+        .fetch()
+        .await?;
+    trace!("Received RBAC policies: {:?}", root.rbac_policies);
+    Ok(root.rbac_policies)
+}
+
 /// List routers.
 pub async fn list_routers<Q: Serialize + Sync + Debug>(
     session: &Session,
@@ -463,6 +585,21 @@ pub async fn list_subnets<Q: Serialize + Sync + Debug>(
     Ok(root.subnets)
 }
 
+/// List subnet pools.
+pub async fn list_subnet_pools<Q: Serialize + Sync + Debug>(
+    session: &Session,
+    query: &Q,
+) -> Result<Vec<SubnetPool>> {
+    trace!("Listing subnet pools with {:?}", query);
+    let root: SubnetPoolsRoot = session
+        .get(NETWORK, &["subnetpools"])
+        .query(query)
+        .fetch()
+        .await?;
+    trace!("Received subnet pools: {:?}", root.subnetpools);
+    Ok(root.subnetpools)
+}
+
 /// Remove an interface from a router.
 pub async fn remove_router_interface<S>(
     session: &Session,
@@ -19,7 +19,9 @@ mod floatingips;
 mod networks;
 mod ports;
 mod protocol;
+mod rbac_policies;
 mod routers;
+mod subnet_pools;
 mod subnets;
 
 pub use self::floatingips::{FloatingIp, FloatingIpQuery, NewFloatingIp};
@@ -28,8 +30,10 @@ pub use self::ports::{NewPort, Port, PortIpAddress, PortIpRequest, PortQuery};
 pub use self::protocol::{
     AllocationPool, AllowedAddressPair, ConntrackHelper, ExternalGateway, FloatingIpSortKey,
     FloatingIpStatus, Helper, HostRoute, IpVersion, Ipv6Mode, NetworkProtocol, NetworkSortKey,
-    NetworkStatus, PortExtraDhcpOption, PortForwarding, PortSortKey, RouterSortKey, RouterStatus,
-    SubnetSortKey,
+    NetworkStatus, PortExtraDhcpOption, PortForwarding, PortSortKey, RbacAction, RbacObjectType,
+    RouterSortKey, RouterStatus, SubnetSortKey,
 };
+pub use self::rbac_policies::{NewRbacPolicy, RbacPolicy, RbacPolicyQuery};
 pub use self::routers::{NewRouter, Router, RouterQuery};
+pub use self::subnet_pools::{NewSubnetPool, SubnetPool, SubnetPoolQuery};
 pub use self::subnets::{NewSubnet, Subnet, SubnetQuery};
@@ -30,7 +30,7 @@ use super::super::common::{
 use super::super::session::Session;
 use super::super::utils::Query;
 use super::super::waiter::DeletionWaiter;
-use super::super::{Result, Sort};
+use super::super::{Error, ErrorKind, Result, Sort};
 use super::{api, protocol, Network, Subnet};
 
 /// A query to port list.
@@ -81,6 +81,20 @@ pub struct NewPort {
     fixed_ips: Vec<PortIpRequest>,
 }
 
+fn check_port_security(
+    port_security_enabled: Option<bool>,
+    security_groups: &[SecurityGroupRef],
+) -> Result<()> {
+    if port_security_enabled == Some(false) && !security_groups.is_empty() {
+        Err(Error::new(
+            ErrorKind::InvalidInput,
+            "Security groups cannot be set on a port with port security disabled",
+        ))
+    } else {
+        Ok(())
+    }
+}
+
 fn convert_fixed_ips(session: &Session, inner: &mut protocol::Port) -> Vec<PortIpAddress> {
     let mut fixed_ips = Vec::new();
     mem::swap(&mut inner.fixed_ips, &mut fixed_ips);
@@ -244,6 +258,19 @@ impl Port {
         network_id: ref String
     }
 
+    transparent_property! {
+        #[doc = "Whether port security is enabled."]
+        port_security_enabled: Option<bool>
+    }
+
+    update_field! {
+        #[doc = "Configure whether port security is enabled."]
+        #[doc = ""]
+        #[doc = "Security groups must be removed before disabling port security."]
+        set_port_security_enabled, with_port_security_enabled
+            -> port_security_enabled: optional bool
+    }
+
     transparent_property! {
         #[doc = "Port status."]
         status: protocol::NetworkStatus
@@ -272,13 +299,19 @@ impl Port {
     /// Save the changes to the port.
     #[allow(clippy::field_reassign_with_default)]
     pub async fn save(&mut self) -> Result<()> {
+        if self.dirty.contains("port_security_enabled") {
+            check_port_security(
+                self.inner.port_security_enabled,
+                &self.inner.security_groups,
+            )?;
+        }
         let mut update = protocol::PortUpdate::default();
         save_fields! {
             self -> update: admin_state_up extra_dhcp_opts mac_address
         };
         save_option_fields! {
             self -> update: description device_id device_owner dns_domain
-                dns_name name
+                dns_name name port_security_enabled
         };
         let mut inner = api::update_port(&self.session, self.id(), update).await?;
         self.fixed_ips = convert_fixed_ips(&self.session, &mut inner);
@@ -479,6 +512,7 @@ impl NewPort {
                 name: None,
                 // Will be replaced in create()
                 network_id: String::new(),
+                port_security_enabled: None,
                 project_id: None,
                 security_groups: Vec::new(),
                 // Dummy value, not used when serializing
@@ -492,6 +526,7 @@ impl NewPort {
 
     /// Request creation of the port.
     pub async fn create(mut self) -> Result<Port> {
+        self.validate()?;
         self.inner.network_id = self.network.into_verified(&self.session).await?.into();
         for request in self.fixed_ips {
             self.inner.fixed_ips.push(match request {
@@ -581,10 +616,26 @@ impl NewPort {
         set_name, with_name -> name: optional String
     }
 
+    creation_inner_field! {
+        #[doc = "Set whether port security is enabled for the port."]
+        #[doc = ""]
+        #[doc = "A port with port security disabled cannot have security groups. To boot a"]
+        #[doc = "server without port security, create such a port and pass it to"]
+        #[doc = "`NewServer::add_port`."]
+        set_port_security_enabled, with_port_security_enabled -> port_security_enabled: optional bool
+    }
+
     creation_inner_vec! {
         #[doc = "Set security groups for the port."]
         add_security_group, with_security_group -> security_groups: into SecurityGroupRef
     }
+
+    fn validate(&self) -> Result<()> {
+        check_port_security(
+            self.inner.port_security_enabled,
+            &self.inner.security_groups,
+        )
+    }
 }
 
 impl From<Port> for PortRef {
@@ -604,3 +655,59 @@ impl PortRef {
         })
     }
 }
+
+#[cfg(test)]
+mod test {
+    use osauth::NoAuth;
+
+    use super::super::super::session::Session;
+    use super::super::super::ErrorKind;
+    use super::super::protocol;
+    use super::{NewPort, Port};
+
+    async fn new_port() -> NewPort {
+        let auth = NoAuth::new("http://127.0.0.1:9696").unwrap();
+        NewPort::new(Session::new(auth).await.unwrap(), "net".into())
+    }
+
+    #[tokio::test]
+    async fn test_port_security_disabled_body() {
+        let mut port = new_port().await.with_port_security_enabled(false);
+        port.inner.network_id = "net".into();
+        port.validate().unwrap();
+        let body = serde_json::to_value(&port.inner).unwrap();
+        assert_eq!(body["port_security_enabled"], false);
+        assert!(body.get("security_groups").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_port_security_disabled_with_security_groups() {
+        let port = new_port()
+            .await
+            .with_port_security_enabled(false)
+            .with_security_group("default");
+        assert_eq!(port.validate().unwrap_err().kind(), ErrorKind::InvalidInput);
+    }
+
+    #[tokio::test]
+    async fn test_port_save_port_security_with_security_groups() {
+        let auth = NoAuth::new("http://127.0.0.1:9696").unwrap();
+        let inner: protocol::Port = serde_json::from_value(serde_json::json!({
+            "admin_state_up": true,
+            "fixed_ips": [],
+            "id": "port-id",
+            "mac_address": "fa:16:3e:00:00:01",
+            "name": "port",
+            "network_id": "net",
+            "port_security_enabled": true,
+            "security_groups": ["sg-id"],
+            "status": "ACTIVE"
+        }))
+        .unwrap();
+        let mut port = Port::new(Session::new(auth).await.unwrap(), inner);
+        port.set_port_security_enabled(false);
+        // Rejected before any request is made.
+        let err = port.save().await.unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    }
+}
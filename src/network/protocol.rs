@@ -153,6 +153,30 @@ protocol_enum! {
     }
 }
 
+protocol_enum! {
+    #[doc = "Type of an object an RBAC policy applies to."]
+    enum RbacObjectType {
+        AddressGroup = "address_group",
+        AddressScope = "address_scope",
+        Network = "network",
+        QosPolicy = "qos_policy",
+        SecurityGroup = "security_group",
+        SubnetPool = "subnetpool";
+        #[doc = "An object type unknown to this crate."]
+        Other(String)
+    }
+}
+
+protocol_enum! {
+    #[doc = "Access granted by an RBAC policy."]
+    enum RbacAction {
+        AccessAsExternal = "access_as_external",
+        AccessAsShared = "access_as_shared";
+        #[doc = "An action unknown to this crate."]
+        Other(String)
+    }
+}
+
 /// An network.
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct Network {
@@ -391,6 +415,8 @@ pub struct Port {
     pub name: Option<String>,
     pub network_id: String,
     #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub port_security_enabled: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub project_id: Option<String>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub security_groups: Vec<SecurityGroupRef>,
@@ -424,6 +450,8 @@ pub struct PortUpdate {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub name: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub port_security_enabled: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub security_groups: Option<Vec<SecurityGroupRef>>,
 }
 
@@ -704,32 +732,66 @@ pub struct Subnet {
     pub network_id: String,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub project_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subnetpool_id: Option<String>,
     #[serde(default, skip_serializing)]
     pub updated_at: Option<DateTime<FixedOffset>>,
 }
 
-impl Subnet {
-    pub(crate) fn empty(cidr: ipnet::IpNet) -> Subnet {
-        Subnet {
+/// A subnet creation request.
+///
+/// Unlike `Subnet`, the CIDR is optional: it is allocated from the subnet pool if omitted.
+#[derive(Debug, Clone, Serialize)]
+pub struct SubnetCreate {
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub allocation_pools: Vec<AllocationPool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cidr: Option<ipnet::IpNet>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(rename = "enable_dhcp")]
+    pub dhcp_enabled: bool,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub dns_nameservers: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gateway_ip: Option<net::IpAddr>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub host_routes: Vec<HostRoute>,
+    pub ip_version: IpVersion,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ipv6_address_mode: Option<Ipv6Mode>,
+    #[serde(rename = "ipv6_ra_mode", skip_serializing_if = "Option::is_none")]
+    pub ipv6_router_advertisement_mode: Option<Ipv6Mode>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    pub network_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prefixlen: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub project_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subnetpool_id: Option<String>,
+}
+
+impl SubnetCreate {
+    pub(crate) fn new(cidr: Option<ipnet::IpNet>) -> SubnetCreate {
+        SubnetCreate {
             allocation_pools: Vec::new(),
             cidr,
-            created_at: None,
             description: None,
             dhcp_enabled: true,
             dns_nameservers: Vec::new(),
             gateway_ip: None,
             host_routes: Vec::new(),
-            id: String::new(),
-            ip_version: match cidr {
-                ipnet::IpNet::V4(..) => IpVersion::V4,
-                ipnet::IpNet::V6(..) => IpVersion::V6,
-            },
+            // Set on creation.
+            ip_version: IpVersion::V4,
             ipv6_address_mode: None,
             ipv6_router_advertisement_mode: None,
             name: None,
             network_id: String::new(),
+            prefixlen: None,
             project_id: None,
-            updated_at: None,
+            subnetpool_id: None,
         }
     }
 }
@@ -759,6 +821,12 @@ pub struct SubnetRoot {
     pub subnet: Subnet,
 }
 
+/// A subnet creation request.
+#[derive(Debug, Clone, Serialize)]
+pub struct SubnetCreateRoot {
+    pub subnet: SubnetCreate,
+}
+
 /// A subnet.
 #[derive(Debug, Clone, Serialize)]
 pub struct SubnetUpdateRoot {
@@ -855,3 +923,73 @@ pub struct FloatingIpUpdateRoot {
 pub struct FloatingIpsRoot {
     pub floatingips: Vec<FloatingIp>,
 }
+
+/// An RBAC policy.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct RbacPolicy {
+    pub action: RbacAction,
+    #[serde(skip_serializing)]
+    pub id: String,
+    pub object_id: String,
+    pub object_type: RbacObjectType,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project_id: Option<String>,
+    pub target_tenant: String,
+}
+
+/// An RBAC policy.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct RbacPolicyRoot {
+    pub rbac_policy: RbacPolicy,
+}
+
+/// RBAC policies.
+#[derive(Debug, Clone, Deserialize)]
+pub struct RbacPoliciesRoot {
+    pub rbac_policies: Vec<RbacPolicy>,
+}
+
+/// A subnet pool.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct SubnetPool {
+    #[serde(default, skip_serializing)]
+    pub created_at: Option<DateTime<FixedOffset>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_prefixlen: Option<u8>,
+    #[serde(
+        deserialize_with = "empty_as_default",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub description: Option<String>,
+    #[serde(skip_serializing)]
+    pub id: String,
+    #[serde(default, skip_serializing)]
+    pub ip_version: Option<IpVersion>,
+    #[serde(default, skip_serializing_if = "Not::not")]
+    pub is_default: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_prefixlen: Option<u8>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_prefixlen: Option<u8>,
+    pub name: String,
+    pub prefixes: Vec<ipnet::IpNet>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Not::not")]
+    pub shared: bool,
+    #[serde(default, skip_serializing)]
+    pub updated_at: Option<DateTime<FixedOffset>>,
+}
+
+/// A subnet pool.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct SubnetPoolRoot {
+    pub subnetpool: SubnetPool,
+}
+
+/// Subnet pools.
+#[derive(Debug, Clone, Deserialize)]
+pub struct SubnetPoolsRoot {
+    pub subnetpools: Vec<SubnetPool>,
+}
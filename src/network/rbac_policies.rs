@@ -0,0 +1,315 @@
+// Copyright 2026 Dmitry Tantsur <divius.inside@gmail.com>
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! RBAC policy support.
+
+use std::time::Duration;
+
+use async_trait::async_trait;
+use futures::stream::{Stream, TryStreamExt};
+
+use super::super::common::{Refresh, ResourceIterator, ResourceQuery};
+use super::super::session::Session;
+use super::super::utils::Query;
+use super::super::waiter::DeletionWaiter;
+use super::super::Result;
+use super::{api, protocol};
+
+/// Structure representing a single RBAC policy.
+#[derive(Clone, Debug)]
+pub struct RbacPolicy {
+    session: Session,
+    inner: protocol::RbacPolicy,
+}
+
+/// A query to RBAC policy list.
+#[derive(Clone, Debug)]
+pub struct RbacPolicyQuery {
+    session: Session,
+    query: Query,
+    can_paginate: bool,
+}
+
+/// A request to create an RBAC policy.
+#[derive(Clone, Debug)]
+pub struct NewRbacPolicy {
+    session: Session,
+    inner: protocol::RbacPolicy,
+}
+
+impl RbacPolicy {
+    /// Create a new RBAC policy object.
+    pub(crate) fn new(session: Session, inner: protocol::RbacPolicy) -> RbacPolicy {
+        RbacPolicy { session, inner }
+    }
+
+    /// Load an RbacPolicy object.
+    pub(crate) async fn load<Id: AsRef<str>>(session: Session, id: Id) -> Result<RbacPolicy> {
+        let inner = api::get_rbac_policy(&session, id).await?;
+        Ok(RbacPolicy::new(session, inner))
+    }
+
+    transparent_property! {
+        #[doc = "Access granted by the policy."]
+        action: ref protocol::RbacAction
+    }
+
+    transparent_property! {
+        #[doc = "Unique ID."]
+        id: ref String
+    }
+
+    transparent_property! {
+        #[doc = "ID of the object the policy applies to."]
+        object_id: ref String
+    }
+
+    transparent_property! {
+        #[doc = "Type of the object the policy applies to."]
+        object_type: ref protocol::RbacObjectType
+    }
+
+    transparent_property! {
+        #[doc = "ID of the project owning the policy (if available)."]
+        project_id: ref Option<String>
+    }
+
+    transparent_property! {
+        #[doc = "ID of the project the access is granted to (`*` for all projects)."]
+        target_tenant: ref String
+    }
+
+    /// Delete the RBAC policy.
+    pub async fn delete(self) -> Result<DeletionWaiter<RbacPolicy>> {
+        api::delete_rbac_policy(&self.session, &self.inner.id).await?;
+        Ok(DeletionWaiter::new(
+            self,
+            Duration::new(60, 0),
+            Duration::new(1, 0),
+        ))
+    }
+}
+
+#[async_trait]
+impl Refresh for RbacPolicy {
+    /// Refresh the RBAC policy.
+    async fn refresh(&mut self) -> Result<()> {
+        self.inner = api::get_rbac_policy(&self.session, &self.inner.id).await?;
+        Ok(())
+    }
+}
+
+impl RbacPolicyQuery {
+    pub(crate) fn new(session: Session) -> RbacPolicyQuery {
+        RbacPolicyQuery {
+            session,
+            query: Query::new(),
+            can_paginate: true,
+        }
+    }
+
+    /// Add marker to the request.
+    ///
+    /// Using this disables automatic pagination.
+    pub fn with_marker<T: Into<String>>(mut self, marker: T) -> Self {
+        self.can_paginate = false;
+        self.query.push_str("marker", marker);
+        self
+    }
+
+    /// Add limit to the request.
+    ///
+    /// Using this disables automatic pagination.
+    pub fn with_limit(mut self, limit: usize) -> Self {
+        self.can_paginate = false;
+        self.query.push("limit", limit);
+        self
+    }
+
+    query_filter! {
+        #[doc = "Filter by the granted access."]
+        set_action, with_action -> action: protocol::RbacAction
+    }
+
+    query_filter! {
+        #[doc = "Filter by the ID of the object."]
+        set_object_id, with_object_id -> object_id
+    }
+
+    query_filter! {
+        #[doc = "Filter by the type of the object."]
+        set_object_type, with_object_type -> object_type: protocol::RbacObjectType
+    }
+
+    query_filter! {
+        #[doc = "Filter by the ID of the target project."]
+        set_target_tenant, with_target_tenant -> target_tenant
+    }
+
+    /// Convert this query into a stream executing the request.
+    ///
+    /// Returns a `TryStream`, which is a stream with each `next`
+    /// call returning a `Result`.
+    ///
+    /// Note that no requests are done until you start iterating.
+    pub fn into_stream(self) -> impl Stream<Item = Result<RbacPolicy>> {
+        debug!("Fetching RBAC policies with {:?}", self.query);
+        ResourceIterator::new(self).into_stream()
+    }
+
+    /// Execute this request and return all results.
+    ///
+    /// A convenience shortcut for `self.into_stream().try_collect().await`.
+    pub async fn all(self) -> Result<Vec<RbacPolicy>> {
+        self.into_stream().try_collect().await
+    }
+
+    /// Return one and exactly one result.
+    ///
+    /// Fails with `ResourceNotFound` if the query produces no results and
+    /// with `TooManyItems` if the query produces more than one result.
+    pub async fn one(mut self) -> Result<RbacPolicy> {
+        debug!("Fetching one RBAC policy with {:?}", self.query);
+        if self.can_paginate {
+            // We need only one result. We fetch maximum two to be able
+            // to check if the query yieled more than one result.
+            self.query.push("limit", 2);
+        }
+
+        ResourceIterator::new(self).one().await
+    }
+}
+
+#[async_trait]
+impl ResourceQuery for RbacPolicyQuery {
+    type Item = RbacPolicy;
+
+    const DEFAULT_LIMIT: usize = 50;
+
+    async fn can_paginate(&self) -> Result<bool> {
+        Ok(self.can_paginate)
+    }
+
+    fn extract_marker(&self, resource: &Self::Item) -> String {
+        resource.id().clone()
+    }
+
+    async fn fetch_chunk(
+        &self,
+        limit: Option<usize>,
+        marker: Option<String>,
+    ) -> Result<Vec<Self::Item>> {
+        let query = self.query.with_marker_and_limit(limit, marker);
+        Ok(api::list_rbac_policies(&self.session, &query)
+            .await?
+            .into_iter()
+            .map(|item| RbacPolicy::new(self.session.clone(), item))
+            .collect())
+    }
+}
+
+impl NewRbacPolicy {
+    /// Start creating an RBAC policy.
+    pub(crate) fn new(
+        session: Session,
+        object_type: protocol::RbacObjectType,
+        object_id: String,
+        target_tenant: String,
+        action: protocol::RbacAction,
+    ) -> NewRbacPolicy {
+        NewRbacPolicy {
+            session,
+            inner: protocol::RbacPolicy {
+                action,
+                // Dummy value, not used when serializing
+                id: String::new(),
+                object_id,
+                object_type,
+                project_id: None,
+                target_tenant,
+            },
+        }
+    }
+
+    /// Request creation of the RBAC policy.
+    pub async fn create(self) -> Result<RbacPolicy> {
+        let inner = api::create_rbac_policy(&self.session, self.inner).await?;
+        Ok(RbacPolicy::new(self.session, inner))
+    }
+
+    creation_inner_field! {
+        #[doc = "Set the project owning the policy (admin-only)."]
+        set_project_id, with_project_id -> project_id: optional String
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use osauth::NoAuth;
+
+    use super::super::super::session::Session;
+    use super::super::protocol::{self, RbacAction, RbacObjectType};
+    use super::NewRbacPolicy;
+
+    #[tokio::test]
+    async fn test_new_rbac_policy_body() {
+        let auth = NoAuth::new("http://127.0.0.1:9696").unwrap();
+        let policy = NewRbacPolicy::new(
+            Session::new(auth).await.unwrap(),
+            RbacObjectType::Network,
+            "net-id".into(),
+            "project-id".into(),
+            RbacAction::AccessAsShared,
+        );
+        let body = protocol::RbacPolicyRoot {
+            rbac_policy: policy.inner,
+        };
+        assert_eq!(
+            serde_json::to_value(&body).unwrap(),
+            serde_json::json!({
+                "rbac_policy": {
+                    "action": "access_as_shared",
+                    "object_id": "net-id",
+                    "object_type": "network",
+                    "target_tenant": "project-id"
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn test_rbac_policy_unknown_values() {
+        let policy: protocol::RbacPolicy = serde_json::from_value(serde_json::json!({
+            "action": "access_as_readonly",
+            "id": "policy-id",
+            "object_id": "net-id",
+            "object_type": "bgpvpn",
+            "target_tenant": "*"
+        }))
+        .unwrap();
+        assert_eq!(
+            policy.action,
+            RbacAction::Other("access_as_readonly".into())
+        );
+        assert_eq!(policy.object_type, RbacObjectType::Other("bgpvpn".into()));
+        assert_eq!(policy.object_type.to_string(), "bgpvpn");
+
+        let known: RbacAction = serde_json::from_value("access_as_shared".into()).unwrap();
+        assert_eq!(known, RbacAction::AccessAsShared);
+        assert_eq!(
+            serde_json::to_value(&policy).unwrap()["action"],
+            "access_as_readonly"
+        );
+    }
+}
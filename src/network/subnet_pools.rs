@@ -0,0 +1,356 @@
+// Copyright 2026 Dmitry Tantsur <divius.inside@gmail.com>
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Subnet pool support.
+
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::{DateTime, FixedOffset};
+use futures::stream::{Stream, TryStreamExt};
+
+use super::super::common::{Refresh, ResourceIterator, ResourceQuery};
+use super::super::session::Session;
+use super::super::utils::Query;
+use super::super::waiter::DeletionWaiter;
+use super::super::Result;
+use super::{api, protocol};
+
+/// Structure representing a single subnet pool.
+#[derive(Clone, Debug)]
+pub struct SubnetPool {
+    session: Session,
+    inner: protocol::SubnetPool,
+}
+
+/// A query to subnet pool list.
+#[derive(Clone, Debug)]
+pub struct SubnetPoolQuery {
+    session: Session,
+    query: Query,
+    can_paginate: bool,
+}
+
+/// A request to create a subnet pool.
+#[derive(Clone, Debug)]
+pub struct NewSubnetPool {
+    session: Session,
+    inner: protocol::SubnetPool,
+}
+
+impl SubnetPool {
+    /// Create a new subnet pool object.
+    pub(crate) fn new(session: Session, inner: protocol::SubnetPool) -> SubnetPool {
+        SubnetPool { session, inner }
+    }
+
+    /// Load a SubnetPool object.
+    pub(crate) async fn load<Id: AsRef<str>>(session: Session, id: Id) -> Result<SubnetPool> {
+        let inner = api::get_subnet_pool(&session, id).await?;
+        Ok(SubnetPool::new(session, inner))
+    }
+
+    transparent_property! {
+        #[doc = "Creation data and time (if available)."]
+        created_at: Option<DateTime<FixedOffset>>
+    }
+
+    transparent_property! {
+        #[doc = "Prefix length of subnets allocated without an explicit length (if set)."]
+        default_prefixlen: Option<u8>
+    }
+
+    transparent_property! {
+        #[doc = "Subnet pool description."]
+        description: ref Option<String>
+    }
+
+    transparent_property! {
+        #[doc = "Unique ID."]
+        id: ref String
+    }
+
+    transparent_property! {
+        #[doc = "IP protocol version (if available)."]
+        ip_version: Option<protocol::IpVersion>
+    }
+
+    transparent_property! {
+        #[doc = "Whether this is the default subnet pool."]
+        is_default: bool
+    }
+
+    transparent_property! {
+        #[doc = "Maximum prefix length of allocated subnets (if set)."]
+        max_prefixlen: Option<u8>
+    }
+
+    transparent_property! {
+        #[doc = "Minimum prefix length of allocated subnets (if set)."]
+        min_prefixlen: Option<u8>
+    }
+
+    transparent_property! {
+        #[doc = "Subnet pool name."]
+        name: ref String
+    }
+
+    transparent_property! {
+        #[doc = "Prefixes subnets are allocated from."]
+        prefixes: ref Vec<ipnet::IpNet>
+    }
+
+    transparent_property! {
+        #[doc = "ID of the project owning the subnet pool (if available)."]
+        project_id: ref Option<String>
+    }
+
+    transparent_property! {
+        #[doc = "Whether the subnet pool is shared."]
+        shared: bool
+    }
+
+    transparent_property! {
+        #[doc = "Last update data and time (if available)."]
+        updated_at: Option<DateTime<FixedOffset>>
+    }
+
+    /// Delete the subnet pool.
+    pub async fn delete(self) -> Result<DeletionWaiter<SubnetPool>> {
+        api::delete_subnet_pool(&self.session, &self.inner.id).await?;
+        Ok(DeletionWaiter::new(
+            self,
+            Duration::new(60, 0),
+            Duration::new(1, 0),
+        ))
+    }
+}
+
+#[async_trait]
+impl Refresh for SubnetPool {
+    /// Refresh the subnet pool.
+    async fn refresh(&mut self) -> Result<()> {
+        self.inner = api::get_subnet_pool_by_id(&self.session, &self.inner.id).await?;
+        Ok(())
+    }
+}
+
+impl SubnetPoolQuery {
+    pub(crate) fn new(session: Session) -> SubnetPoolQuery {
+        SubnetPoolQuery {
+            session,
+            query: Query::new(),
+            can_paginate: true,
+        }
+    }
+
+    /// Add marker to the request.
+    ///
+    /// Using this disables automatic pagination.
+    pub fn with_marker<T: Into<String>>(mut self, marker: T) -> Self {
+        self.can_paginate = false;
+        self.query.push_str("marker", marker);
+        self
+    }
+
+    /// Add limit to the request.
+    ///
+    /// Using this disables automatic pagination.
+    pub fn with_limit(mut self, limit: usize) -> Self {
+        self.can_paginate = false;
+        self.query.push("limit", limit);
+        self
+    }
+
+    query_filter! {
+        #[doc = "Filter by whether the subnet pool is the default one."]
+        set_default, with_default -> is_default: bool
+    }
+
+    query_filter! {
+        #[doc = "Filter by subnet pool name."]
+        set_name, with_name -> name
+    }
+
+    query_filter! {
+        #[doc = "Filter by whether the subnet pool is shared."]
+        set_shared, with_shared -> shared: bool
+    }
+
+    /// Convert this query into a stream executing the request.
+    ///
+    /// Returns a `TryStream`, which is a stream with each `next`
+    /// call returning a `Result`.
+    ///
+    /// Note that no requests are done until you start iterating.
+    pub fn into_stream(self) -> impl Stream<Item = Result<SubnetPool>> {
+        debug!("Fetching subnet pools with {:?}", self.query);
+        ResourceIterator::new(self).into_stream()
+    }
+
+    /// Execute this request and return all results.
+    ///
+    /// A convenience shortcut for `self.into_stream().try_collect().await`.
+    pub async fn all(self) -> Result<Vec<SubnetPool>> {
+        self.into_stream().try_collect().await
+    }
+
+    /// Return one and exactly one result.
+    ///
+    /// Fails with `ResourceNotFound` if the query produces no results and
+    /// with `TooManyItems` if the query produces more than one result.
+    pub async fn one(mut self) -> Result<SubnetPool> {
+        debug!("Fetching one subnet pool with {:?}", self.query);
+        if self.can_paginate {
+            // We need only one result. We fetch maximum two to be able
+            // to check if the query yieled more than one result.
+            self.query.push("limit", 2);
+        }
+
+        ResourceIterator::new(self).one().await
+    }
+}
+
+#[async_trait]
+impl ResourceQuery for SubnetPoolQuery {
+    type Item = SubnetPool;
+
+    const DEFAULT_LIMIT: usize = 50;
+
+    async fn can_paginate(&self) -> Result<bool> {
+        Ok(self.can_paginate)
+    }
+
+    fn extract_marker(&self, resource: &Self::Item) -> String {
+        resource.id().clone()
+    }
+
+    async fn fetch_chunk(
+        &self,
+        limit: Option<usize>,
+        marker: Option<String>,
+    ) -> Result<Vec<Self::Item>> {
+        let query = self.query.with_marker_and_limit(limit, marker);
+        Ok(api::list_subnet_pools(&self.session, &query)
+            .await?
+            .into_iter()
+            .map(|item| SubnetPool::new(self.session.clone(), item))
+            .collect())
+    }
+}
+
+impl NewSubnetPool {
+    /// Start creating a subnet pool.
+    pub(crate) fn new(
+        session: Session,
+        name: String,
+        prefixes: Vec<ipnet::IpNet>,
+    ) -> NewSubnetPool {
+        NewSubnetPool {
+            session,
+            inner: protocol::SubnetPool {
+                created_at: None,
+                default_prefixlen: None,
+                description: None,
+                // Dummy value, not used when serializing
+                id: String::new(),
+                ip_version: None,
+                is_default: false,
+                max_prefixlen: None,
+                min_prefixlen: None,
+                name,
+                prefixes,
+                project_id: None,
+                shared: false,
+                updated_at: None,
+            },
+        }
+    }
+
+    /// Request creation of the subnet pool.
+    pub async fn create(self) -> Result<SubnetPool> {
+        let inner = api::create_subnet_pool(&self.session, self.inner).await?;
+        Ok(SubnetPool::new(self.session, inner))
+    }
+
+    creation_inner_field! {
+        #[doc = "Set the prefix length of subnets allocated without an explicit length."]
+        set_default_prefixlen, with_default_prefixlen -> default_prefixlen: optional u8
+    }
+
+    creation_inner_field! {
+        #[doc = "Set description of the subnet pool."]
+        set_description, with_description -> description: optional String
+    }
+
+    creation_inner_field! {
+        #[doc = "Configure whether this is the default subnet pool."]
+        set_default, with_default -> is_default: bool
+    }
+
+    creation_inner_field! {
+        #[doc = "Set the maximum prefix length of allocated subnets."]
+        set_max_prefixlen, with_max_prefixlen -> max_prefixlen: optional u8
+    }
+
+    creation_inner_field! {
+        #[doc = "Set the minimum prefix length of allocated subnets."]
+        set_min_prefixlen, with_min_prefixlen -> min_prefixlen: optional u8
+    }
+
+    creation_inner_field! {
+        #[doc = "Set the project owning the subnet pool (admin-only)."]
+        set_project_id, with_project_id -> project_id: optional String
+    }
+
+    creation_inner_field! {
+        #[doc = "Configure whether the subnet pool is shared across all projects."]
+        set_shared, with_shared -> shared: bool
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use osauth::NoAuth;
+
+    use super::super::super::session::Session;
+    use super::super::protocol;
+    use super::NewSubnetPool;
+
+    #[tokio::test]
+    async fn test_new_subnet_pool_body() {
+        let auth = NoAuth::new("http://127.0.0.1:9696").unwrap();
+        let pool = NewSubnetPool::new(
+            Session::new(auth).await.unwrap(),
+            "pool".into(),
+            vec!["10.0.0.0/16".parse().unwrap()],
+        )
+        .with_default_prefixlen(24)
+        .with_shared(true);
+        let body = protocol::SubnetPoolRoot {
+            subnetpool: pool.inner,
+        };
+        assert_eq!(
+            serde_json::to_value(&body).unwrap(),
+            serde_json::json!({
+                "subnetpool": {
+                    "default_prefixlen": 24,
+                    "name": "pool",
+                    "prefixes": ["10.0.0.0/16"],
+                    "shared": true
+                }
+            })
+        );
+    }
+}
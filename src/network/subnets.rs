@@ -26,7 +26,7 @@ use super::super::common::{NetworkRef, Refresh, ResourceIterator, ResourceQuery,
 use super::super::session::Session;
 use super::super::utils::Query;
 use super::super::waiter::DeletionWaiter;
-use super::super::{Result, Sort};
+use super::super::{Error, ErrorKind, Result, Sort};
 use super::{api, protocol, Network};
 
 /// A query to subnet list.
@@ -50,7 +50,7 @@ pub struct Subnet {
 #[derive(Clone, Debug)]
 pub struct NewSubnet {
     session: Session,
-    inner: protocol::Subnet,
+    inner: protocol::SubnetCreate,
     network: NetworkRef,
 }
 
@@ -183,6 +183,11 @@ impl Subnet {
         network_id: ref String
     }
 
+    transparent_property! {
+        #[doc = "ID of the subnet pool this subnet was allocated from (if any)."]
+        subnetpool_id: ref Option<String>
+    }
+
     transparent_property! {
         #[doc = "Last update data and time (if available)."]
         updated_at: Option<DateTime<FixedOffset>>
@@ -387,26 +392,56 @@ impl ResourceQuery for SubnetQuery {
 
 impl NewSubnet {
     /// Start creating a subnet.
-    pub(crate) fn new(session: Session, network: NetworkRef, cidr: ipnet::IpNet) -> NewSubnet {
+    pub(crate) fn new(
+        session: Session,
+        network: NetworkRef,
+        cidr: Option<ipnet::IpNet>,
+    ) -> NewSubnet {
         NewSubnet {
             session,
-            inner: protocol::Subnet::empty(cidr),
+            inner: protocol::SubnetCreate::new(cidr),
             network,
         }
     }
 
     /// Request creation of the subnet.
+    ///
+    /// Without a CIDR, the subnet pool is looked up to find the IP version.
     pub async fn create(mut self) -> Result<Subnet> {
-        self.inner.network_id = self.network.into_verified(&self.session).await?.into();
+        if self.inner.prefixlen.is_some() && self.inner.subnetpool_id.is_none() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "A prefix length can only be used with a subnet pool",
+            ));
+        }
         self.inner.ip_version = match self.inner.cidr {
-            ipnet::IpNet::V4(..) => protocol::IpVersion::V4,
-            ipnet::IpNet::V6(..) => protocol::IpVersion::V6,
+            Some(ipnet::IpNet::V4(..)) => protocol::IpVersion::V4,
+            Some(ipnet::IpNet::V6(..)) => protocol::IpVersion::V6,
+            None => self.pool_ip_version().await?,
         };
+        self.inner.network_id = self.network.into_verified(&self.session).await?.into();
 
         let subnet = api::create_subnet(&self.session, self.inner).await?;
         Ok(Subnet::new(self.session, subnet))
     }
 
+    async fn pool_ip_version(&mut self) -> Result<protocol::IpVersion> {
+        let pool_id = self.inner.subnetpool_id.as_ref().ok_or_else(|| {
+            Error::new(
+                ErrorKind::InvalidInput,
+                "A CIDR or a subnet pool is required to create a subnet",
+            )
+        })?;
+        let pool = api::get_subnet_pool(&self.session, pool_id).await?;
+        self.inner.subnetpool_id = Some(pool.id);
+        pool.ip_version.ok_or_else(|| {
+            Error::new(
+                ErrorKind::InvalidResponse,
+                "Subnet pool does not report its IP version",
+            )
+        })
+    }
+
     creation_inner_vec! {
         #[doc = "Allocation pool(s) for the subnet (the default is the whole CIDR)."]
         add_allocation_pool, with_allocation_pool -> allocation_pools: protocol::AllocationPool
@@ -414,7 +449,9 @@ impl NewSubnet {
 
     creation_inner_field! {
         #[doc = "Set CIDR of the subnet."]
-        set_cidr, with_cidr -> cidr: ipnet::IpNet
+        #[doc = ""]
+        #[doc = "Can be omitted when allocating from a subnet pool."]
+        set_cidr, with_cidr -> cidr: optional ipnet::IpNet
     }
 
     creation_inner_field! {
@@ -454,6 +491,21 @@ impl NewSubnet {
         set_name, with_name -> name: optional String
     }
 
+    creation_inner_field! {
+        #[doc = "Set the prefix length of a subnet allocated from a subnet pool."]
+        #[doc = ""]
+        #[doc = "Only used without a CIDR. The default prefix length of the pool is used if unset."]
+        set_prefixlen, with_prefixlen -> prefixlen: optional u8
+    }
+
+    creation_inner_field! {
+        #[doc = "Allocate the subnet from the subnet pool with this ID."]
+        #[doc = ""]
+        #[doc = "If a CIDR is set, it must belong to one of the prefixes of the pool. Otherwise a"]
+        #[doc = "CIDR is allocated from the pool, and the pool can also be given by name."]
+        set_subnetpool_id, with_subnetpool_id -> subnetpool_id: optional String
+    }
+
     /// Set the network of the subnet.
     pub fn set_network<N>(&mut self, value: N)
     where
@@ -489,3 +541,69 @@ impl SubnetRef {
         })
     }
 }
+
+#[cfg(test)]
+mod test {
+    use osauth::NoAuth;
+
+    use super::super::super::session::Session;
+    use super::super::super::ErrorKind;
+    use super::super::protocol;
+    use super::NewSubnet;
+
+    async fn new_subnet() -> NewSubnet {
+        let auth = NoAuth::new("http://127.0.0.1:9696").unwrap();
+        NewSubnet::new(Session::new(auth).await.unwrap(), "net".into(), None)
+    }
+
+    #[tokio::test]
+    async fn test_new_subnet_from_pool_body() {
+        let mut subnet = new_subnet()
+            .await
+            .with_subnetpool_id("pool-id")
+            .with_prefixlen(26);
+        subnet.inner.network_id = "net".into();
+        let body = protocol::SubnetCreateRoot {
+            subnet: subnet.inner,
+        };
+        assert_eq!(
+            serde_json::to_value(&body).unwrap(),
+            serde_json::json!({
+                "subnet": {
+                    "enable_dhcp": true,
+                    "ip_version": 4,
+                    "network_id": "net",
+                    "prefixlen": 26,
+                    "subnetpool_id": "pool-id"
+                }
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_new_subnet_requires_cidr_or_pool() {
+        let err = new_subnet().await.create().await.unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput);
+
+        let err = new_subnet()
+            .await
+            .with_cidr("10.0.0.0/24".parse::<ipnet::IpNet>().unwrap())
+            .with_prefixlen(26)
+            .create()
+            .await
+            .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn test_subnet_requires_cidr() {
+        let subnet = serde_json::json!({
+            "enable_dhcp": true,
+            "id": "subnet-id",
+            "ip_version": 4,
+            "name": "subnet",
+            "network_id": "net"
+        });
+        assert!(serde_json::from_value::<protocol::Subnet>(subnet).is_err());
+    }
+}
@@ -0,0 +1,190 @@
+// Copyright 2026 Dmitry Tantsur <divius.inside@gmail.com>
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Registry of several named clouds.
+
+use std::collections::{hash_map, HashMap};
+
+use osauth::CloudConfig;
+
+use super::{Cloud, Error, ErrorKind, Result};
+
+#[derive(Debug, Clone)]
+enum Entry {
+    Config(Box<CloudConfig>),
+    Cloud(Cloud),
+}
+
+/// A collection of named clouds.
+///
+/// Clouds are only authenticated when accessed for the first time, so a registry can hold
+/// any number of clouds without contacting them in advance.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// # async fn registry() -> openstack::Result<()> {
+/// let mut registry = openstack::CloudRegistry::from_config(&["prod", "staging"])?;
+/// let servers = registry.cloud("prod").await?.find_servers().all().await?;
+/// # Ok(()) }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CloudRegistry {
+    clouds: HashMap<String, Entry>,
+}
+
+impl CloudRegistry {
+    /// Create an empty registry.
+    pub fn new() -> CloudRegistry {
+        CloudRegistry::default()
+    }
+
+    /// Create a registry with the given clouds from `clouds.yaml`.
+    ///
+    /// The configuration files are read immediately, but no authentication happens.
+    pub fn from_config<I, S>(cloud_names: I) -> Result<CloudRegistry>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut result = CloudRegistry::new();
+        for name in cloud_names {
+            let config = CloudConfig::from_config(name.as_ref())?;
+            result.add_cloud_config(name.as_ref(), config);
+        }
+        Ok(result)
+    }
+
+    /// Add a cloud configuration under the given name.
+    ///
+    /// Replaces the cloud with the same name if it exists.
+    pub fn add_cloud_config<S: Into<String>>(&mut self, name: S, config: CloudConfig) {
+        let _ = self
+            .clouds
+            .insert(name.into(), Entry::Config(Box::new(config)));
+    }
+
+    /// Add a cloud configuration under the given name.
+    pub fn with_cloud_config<S: Into<String>>(mut self, name: S, config: CloudConfig) -> Self {
+        self.add_cloud_config(name, config);
+        self
+    }
+
+    /// Add an existing cloud under the given name.
+    ///
+    /// Replaces the cloud with the same name if it exists.
+    pub fn add_cloud<S: Into<String>>(&mut self, name: S, cloud: Cloud) {
+        let _ = self.clouds.insert(name.into(), Entry::Cloud(cloud));
+    }
+
+    /// Add an existing cloud under the given name.
+    pub fn with_cloud<S: Into<String>>(mut self, name: S, cloud: Cloud) -> Self {
+        self.add_cloud(name, cloud);
+        self
+    }
+
+    /// Whether a cloud with this name exists.
+    #[inline]
+    pub fn contains<S: AsRef<str>>(&self, name: S) -> bool {
+        self.clouds.contains_key(name.as_ref())
+    }
+
+    /// Names of all clouds in the registry (in no particular order).
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.clouds.keys().map(String::as_str)
+    }
+
+    /// Get a cloud by its name, authenticating if it is used for the first time.
+    ///
+    /// The returned cloud shares its session with the one in the registry, so cloning it is
+    /// cheap. Fails with `InvalidConfig` if no such cloud exists. If authentication fails,
+    /// the configuration is kept and authentication is retried on the next call.
+    pub async fn cloud<S: AsRef<str>>(&mut self, name: S) -> Result<Cloud> {
+        let name = name.as_ref();
+        let mut entry = match self.clouds.entry(name.to_string()) {
+            hash_map::Entry::Occupied(entry) => entry,
+            hash_map::Entry::Vacant(..) => {
+                return Err(Error::new(
+                    ErrorKind::InvalidConfig,
+                    format!("Cloud {} is not in the registry", name),
+                ))
+            }
+        };
+        let cloud = match entry.get() {
+            Entry::Cloud(cloud) => cloud.clone(),
+            Entry::Config(config) => {
+                debug!("Authenticating cloud {}", name);
+                let cloud = Cloud::from_cloud_config(config.as_ref().clone()).await?;
+                let _ = entry.insert(Entry::Cloud(cloud.clone()));
+                cloud
+            }
+        };
+        Ok(cloud)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use osauth::CloudConfig;
+
+    use super::super::ErrorKind;
+    use super::CloudRegistry;
+
+    #[tokio::test]
+    async fn test_registry_from_fixture() {
+        // Contents of the `clouds` section of a clouds.yaml
+        let clouds: HashMap<String, CloudConfig> = serde_json::from_value(serde_json::json!({
+            "prod": {
+                "auth_type": "http_basic",
+                "auth": {
+                    "endpoint": "http://prod.example.com:6385",
+                    "username": "admin",
+                    "password": "pa$$w0rd"
+                },
+                "region_name": "RegionOne"
+            },
+            "staging": {
+                "auth_type": "none",
+                "auth": {
+                    "endpoint": "http://staging.example.com:6385"
+                }
+            }
+        }))
+        .unwrap();
+        let mut registry = CloudRegistry::new();
+        for (name, config) in clouds {
+            registry.add_cloud_config(name, config);
+        }
+
+        let mut names: Vec<_> = registry.names().collect();
+        names.sort_unstable();
+        assert_eq!(names, vec!["prod", "staging"]);
+        assert!(registry.contains("prod"));
+
+        let prod = registry.cloud("prod").await.unwrap();
+        assert_eq!(prod.endpoint_filters().region.as_deref(), Some("RegionOne"));
+        let staging = registry.cloud("staging").await.unwrap();
+        assert_eq!(staging.endpoint_filters().region, None);
+
+        let err = registry.cloud("dev").await.unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidConfig);
+        assert!(!registry.contains("dev"));
+
+        // Authenticated clouds are kept.
+        let prod = registry.cloud("prod").await.unwrap();
+        assert_eq!(prod.endpoint_filters().region.as_deref(), Some("RegionOne"));
+    }
+}
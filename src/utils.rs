@@ -22,6 +22,7 @@ use std::fmt;
 use std::hash::Hash;
 
 use futures::{pin_mut, Stream, TryStreamExt};
+use serde::de::DeserializeOwned;
 use serde::{Serialize, Serializer};
 
 use super::{Error, ErrorKind, Result};
@@ -69,6 +70,11 @@ impl Query {
         self.0.push((param.into(), value.into()))
     }
 
+    /// Remove all items with the given name from the query.
+    pub fn remove(&mut self, param: &str) {
+        self.0.retain(|(key, _)| key != param)
+    }
+
     /// Add marker and limit to the query and clone it.
     pub fn with_marker_and_limit(&self, limit: Option<usize>, marker: Option<String>) -> Query {
         let mut new = self.clone();
@@ -210,6 +216,26 @@ pub fn endpoint_not_found<D: fmt::Display>(service_type: D) -> Error {
     )
 }
 
+/// Maximum length of a response body included in an error message.
+const MAX_BODY_IN_ERROR: usize = 1024;
+
+/// Parse a raw JSON response body, keeping the body in the error message on failure.
+///
+/// Long bodies are truncated to `MAX_BODY_IN_ERROR` characters.
+pub fn from_raw_json<T: DeserializeOwned>(body: &serde_json::Value) -> Result<T> {
+    T::deserialize(body).map_err(|err| {
+        let mut body = body.to_string();
+        if let Some((idx, _)) = body.char_indices().nth(MAX_BODY_IN_ERROR) {
+            body.truncate(idx);
+            body.push_str("...");
+        }
+        Error::new(
+            ErrorKind::InvalidResponse,
+            format!("Cannot parse response {body}: {err}"),
+        )
+    })
+}
+
 pub async fn try_one<T, S>(stream: S) -> Result<T>
 where
     S: Stream<Item = Result<T>>,